@@ -1,16 +1,431 @@
 use dioxus::prelude::*;
 use futures_util::StreamExt;
+use std::collections::HashMap;
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
-use crate::{cmd, helpers, logger, parameter, settings};
+use crate::cargo_task::{self, CargoOutcome, CargoTask};
+use crate::templates::{PlaceholderKind, TemplateEntry, TemplateRef};
+use crate::{
+    cmd, docker, doctor, editor, engine, helpers, logger, parameter, probe_firmware, project,
+    settings, template_cache, templates, udev,
+};
 
 /// Actions dispatched from UI buttons into a single coroutine.
 enum AppAction {
     StartDap,
     StopDap,
     StartDocker,
+    StopDocker,
+    LoadDiskUsage,
+    Prune(docker::PruneKind),
     OpenProject(String),
     DetectDevice,
+    WriteLaunchConfig(String),
+    CheckProject(String),
+    FixProject(String, project::CheckFix),
+    EditProjectSettings(String),
+    CheckEditorSetup,
+    InstallExtensions(std::path::PathBuf, Vec<String>),
+    ImportSettings(std::path::PathBuf),
+    ExportSettings(std::path::PathBuf, bool),
+    LoadTemplateOptions(TemplateEntry, Option<TemplateRef>, bool),
+    CacheTemplate(TemplateEntry),
+    ImportTemplateArchive(std::path::PathBuf),
+    ExportTemplateCache(std::path::PathBuf),
+    GenerateProject(cmd::GenerateRequest, bool),
+    ImportProject(std::path::PathBuf),
+    DevContainer(String, DevContainerAction),
+    RunCargo(String, CargoTask),
+    PrepareEnvironments,
+    PrepareTemplate(TemplateEntry),
+    OpenDiagnostic(cargo_task::Diagnostic),
+    RunDoctor,
+    ExportDoctorReport(std::path::PathBuf, bool),
+    GenerateUdevRules,
+    InstallUdevRules(std::path::PathBuf),
+    WaitForBootloader,
+    FlashFirmware(std::path::PathBuf, std::path::PathBuf),
+}
+
+/// Actions offered for a project's Dev Container in History.
+#[derive(Clone, Copy, PartialEq)]
+enum DevContainerAction {
+    Start,
+    Stop,
+    Remove,
+    Rebuild,
+}
+
+impl DevContainerAction {
+    fn label(self) -> &'static str {
+        match self {
+            DevContainerAction::Start => "Start",
+            DevContainerAction::Stop => "Stop",
+            DevContainerAction::Remove => "Remove",
+            DevContainerAction::Rebuild => "Rebuild",
+        }
+    }
+
+    /// What the confirmation dialog says will be lost, for actions that
+    /// delete something.
+    fn warning(self) -> Option<&'static str> {
+        match self {
+            DevContainerAction::Remove => Some(
+                "The container is deleted with everything changed inside it outside the project folder.",
+            ),
+            DevContainerAction::Rebuild => Some(
+                "The container and the image built for it are deleted, then built again from the Dev Container config.",
+            ),
+            DevContainerAction::Start | DevContainerAction::Stop => None,
+        }
+    }
+}
+
+/// Steps of the guided probe firmware update.
+#[derive(Clone, PartialEq)]
+enum FirmwareStep {
+    /// Asking for BOOTSEL; `waiting` once the app looks for the drive.
+    Bootloader {
+        waiting: bool,
+    },
+    /// The RP2040 bootloader drive is mounted; pick the UF2 file.
+    Flash {
+        drive: std::path::PathBuf,
+    },
+    /// Copying the file and waiting for the probe to come back.
+    Verify,
+    Done(String),
+    Failed(String),
+}
+
+/// Flashes `file`, then waits for the probe to reboot and reads its firmware
+/// information again to confirm the update.
+fn update_probe_firmware(
+    file: &std::path::Path,
+    drive: &std::path::Path,
+) -> Result<(cmd::ProbeInfo, probe_firmware::ProbeFirmware), String> {
+    let blocks = probe_firmware::validate_uf2(file)?;
+    crate::log_info(format!(
+        "Flashing {} ({} blocks) to {}",
+        file.display(),
+        blocks,
+        drive.display()
+    ));
+    probe_firmware::flash_uf2(file, drive)?;
+    probe_firmware::wait_for(Duration::from_secs(30), || {
+        probe_firmware::find_bootloader_drive()
+            .is_none()
+            .then_some(())
+    })
+    .ok_or("The bootloader drive is still mounted; the file may not have been accepted")?;
+    let probe = probe_firmware::wait_for(Duration::from_secs(30), probe_firmware::find_baker_link)
+        .ok_or("The Baker link probe did not come back after the update. Replug the board.")?;
+    // The CMSIS-DAP interface may need a moment after enumeration
+    let firmware =
+        probe_firmware::wait_for(Duration::from_secs(5), || probe_firmware::read(&probe).ok())
+            .map_or_else(|| probe_firmware::read(&probe), Ok)?;
+    Ok((probe, firmware))
+}
+
+/// Starts the DAP server again after the firmware update stopped it.
+fn resume_dap_server() -> Result<(), String> {
+    let mut server = crate::dap_server()
+        .lock()
+        .map_err(|_| "DAP server restart: server unavailable".to_string())?;
+    let tx = crate::display_buffer()
+        .lock()
+        .map_err(|_| "DAP server restart: log buffer unavailable".to_string())?
+        .sender();
+    server.start(tx)?;
+    crate::log_info(format!(
+        "probe-rs DAP Server restarted on {}:{}",
+        server.ip, server.port
+    ));
+    Ok(())
+}
+
+/// Makes sure the project's Dev Container is running, then runs `task` in it.
+fn run_cargo_task(path: &str, task: CargoTask) -> Result<CargoOutcome, String> {
+    let project_dir = std::path::Path::new(path);
+    let containers = docker::list_dev_containers()?;
+    let container = match project::find_dev_container(project_dir, &containers) {
+        Some(container) => container.clone(),
+        None => {
+            let cli = cmd::find_engine_cli("devcontainer").ok_or_else(|| {
+                format!(
+                    "No Dev Container found for {}. Open the project in its Dev Container once to create it.",
+                    path
+                )
+            })?;
+            crate::log_info(format!("Creating Dev Container: {}", path));
+            cmd::dev_container_up(&cli, path, false)?;
+            let containers = docker::list_dev_containers()?;
+            project::find_dev_container(project_dir, &containers)
+                .cloned()
+                .ok_or_else(|| format!("Dev Container for {} did not start", path))?
+        }
+    };
+    if !container.is_running() {
+        crate::log_info(format!("Starting Dev Container: {}", container.name));
+        docker::start_container(&container.id)?;
+    }
+    crate::log_info(format!("cargo {} in {}", task.command(), container.name));
+    cmd::run_cargo_in_container(project_dir, &container.id, task)
+}
+
+/// A folder whose Dev Container images are prepared: a history project, or a
+/// template before any project exists.
+struct PrepareTarget {
+    name: String,
+    dir: std::path::PathBuf,
+    /// Build a Dockerfile config with the devcontainer CLI. Templates are not
+    /// built since their files still contain placeholders.
+    build: bool,
+}
+
+/// Pulls the images `target` needs, skipping ones already present, and builds
+/// Dockerfile configs when the devcontainer CLI is installed.
+fn prepare_target(
+    target: &PrepareTarget,
+    progress: &tokio::sync::mpsc::UnboundedSender<String>,
+) -> Result<(), String> {
+    let (images, builds) = project::dev_container_images(&target.dir)?;
+    for image in images {
+        if docker::image_exists(&image) {
+            continue;
+        }
+        crate::log_info(format!("Pulling {} for {}", image, target.name));
+        let mut last = String::new();
+        docker::pull_image(&image, &mut |status, percent| {
+            let message = match percent {
+                Some(percent) => format!("Pulling {} {}%", image, percent),
+                None => format!("Pulling {}: {}", image, status),
+            };
+            if message != last {
+                let _ = progress.send(message.clone());
+                last = message;
+            }
+        })?;
+        crate::log_info(format!("Pulled {}", image));
+    }
+    if builds && target.build {
+        if let Some(cli) = cmd::find_engine_cli("devcontainer") {
+            let _ = progress.send(format!("Building the Dev Container of {}", target.name));
+            cmd::dev_container_build(&cli, &target.dir.to_string_lossy())?;
+            crate::log_info(format!("Dev Container built: {}", target.name));
+        }
+    }
+    Ok(())
+}
+
+/// Folder of `template` with its Dev Container config, caching Git templates
+/// that are not cached yet.
+fn template_dir(template: &TemplateEntry) -> Result<std::path::PathBuf, String> {
+    if let templates::TemplateSource::Path(path) = &template.source {
+        return Ok(std::path::PathBuf::from(path));
+    }
+    if template_cache::find_cached(template).is_none() {
+        crate::log_info(format!("Caching template {}...", template.name));
+        template_cache::cache_template(template)?;
+    }
+    template_cache::find_cached(template)
+        .map(|(_, dir)| dir)
+        .ok_or_else(|| format!("Template {} is not cached", template.name))
+}
+
+/// Prepares `targets` one after another on a worker thread and shows the
+/// progress in `prepare_status` until all are done.
+async fn prepare_environments(
+    targets: Vec<PrepareTarget>,
+    mut prepare_status: Signal<Option<String>>,
+) {
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        for target in &targets {
+            let _ = tx.send(format!("Preparing {}", target.name));
+            if let Err(e) = prepare_target(target, &tx) {
+                crate::log_error(format!("Preparing {} failed: {}", target.name, e));
+            }
+        }
+    });
+    while let Some(message) = rx.recv().await {
+        prepare_status.set(Some(message));
+    }
+    prepare_status.set(None);
+}
+
+/// Image names the history projects need, normalized with
+/// [`docker::normalize_image`], plus the folders of the projects whose
+/// images VS Code builds.
+fn history_images(history: &[settings::HistoryEntry]) -> (Vec<String>, Vec<std::path::PathBuf>) {
+    let containers = docker::list_dev_containers().unwrap_or_default();
+    let mut images = Vec::new();
+    let mut dirs = Vec::new();
+    for entry in history.iter().filter(|entry| entry.devcontainer) {
+        let dir = std::path::PathBuf::from(&entry.path);
+        if let Ok((config_images, _)) = project::dev_container_images(&dir) {
+            images.extend(config_images.iter().map(|i| docker::normalize_image(i)));
+        }
+        if let Some(container) = project::find_dev_container(&dir, &containers) {
+            images.push(docker::normalize_image(&container.image));
+        }
+        dirs.push(dir);
+    }
+    (images, dirs)
+}
+
+/// Runs `kind` on a worker thread, keeping the images of history projects.
+fn run_prune(kind: docker::PruneKind, history: Vec<settings::HistoryEntry>) -> Result<u64, String> {
+    let (images, dirs) = history_images(&history);
+    docker::prune(kind, &|tag| project::is_needed_image(tag, &images, &dirs))
+}
+
+/// Lists the Dev Containers on a worker thread; empty when Docker is down.
+async fn load_dev_containers() -> Vec<docker::DevContainer> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(docker::list_dev_containers().unwrap_or_default());
+    });
+    rx.await.unwrap_or_default()
+}
+
+/// Runs `action` on the Dev Container of `path`. Rebuild uses the devcontainer
+/// CLI when installed; otherwise the container and its image are removed so
+/// the next "Open in Dev Container" builds them again.
+fn run_dev_container_action(path: &str, action: DevContainerAction) -> Result<String, String> {
+    let containers = docker::list_dev_containers()?;
+    let container = project::find_dev_container(std::path::Path::new(path), &containers);
+    if action == DevContainerAction::Rebuild {
+        if let Some(cli) = cmd::find_engine_cli("devcontainer") {
+            crate::log_info(format!("Rebuilding Dev Container: {}", path));
+            cmd::dev_container_up(&cli, path, true)?;
+            return Ok(format!("Dev Container rebuilt: {}", path));
+        }
+    }
+    let container = container.ok_or_else(|| format!("No Dev Container found for {}", path))?;
+    let done = match action {
+        DevContainerAction::Start => docker::start_container(&container.id).map(|_| "started")?,
+        DevContainerAction::Stop => docker::stop_container(&container.id).map(|_| "stopped")?,
+        DevContainerAction::Remove => docker::remove_container(&container.id).map(|_| "removed")?,
+        DevContainerAction::Rebuild => {
+            docker::remove_container(&container.id)?;
+            // Only the image built for this project; a shared base image stays
+            if project::is_project_built_image(std::path::Path::new(path), &container.image) {
+                if let Err(e) = docker::remove_image(&container.image) {
+                    crate::log_error(format!("Dev Container image kept: {}", e));
+                }
+            }
+            "removed (open the project in its Dev Container to rebuild it)"
+        }
+    };
+    Ok(format!("Dev Container {}: {}", done, container.name))
+}
+
+/// Starts the container engine and opens `path` in the configured editor
+/// without blocking the UI.
+async fn open_in_editor(path: String) {
+    std::thread::spawn(|| {
+        let engine = engine::selected();
+        if !matches!(engine.is_running(), Ok(true)) {
+            let _ = engine.start();
+        }
+    });
+    let s = settings::load();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let editor_path = path.clone();
+    std::thread::spawn(move || {
+        let uri = (s.open_in_container && s.editor.supports_dev_container())
+            .then(|| project::dev_container_uri(std::path::Path::new(&editor_path)))
+            .flatten();
+        if let Some(uri) = uri {
+            match cmd::open_in_dev_container(s.editor, &uri) {
+                Ok(()) => {
+                    crate::log_info(format!("Opening Dev Container: {}", uri));
+                    let _ = tx.send(Ok(()));
+                    return;
+                }
+                Err(e) => crate::log_error(format!(
+                    "Dev Container open failed, opening the folder instead: {}",
+                    e
+                )),
+            }
+        }
+        let _ = tx.send(cmd::open_in_editor(
+            s.editor,
+            &s.editor_command,
+            &editor_path,
+        ));
+    });
+    match rx.await {
+        Ok(Ok(())) => crate::log_info(format!("{} opened: {}", s.editor.label(), path)),
+        Ok(Err(e)) => crate::log_error(format!("{} failed to open: {}", s.editor.label(), e)),
+        Err(_) => crate::log_error("Open editor: channel closed"),
+    }
+}
+
+/// Runs [`project::check_project`] on a worker thread.
+async fn run_project_check(path: String, ctx: project::CheckContext) -> Vec<project::CheckIssue> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(project::check_project(std::path::Path::new(&path), &ctx));
+    });
+    // A worker that died must not read as a clean project
+    rx.await.unwrap_or_else(|_| {
+        crate::log_error("Project check: worker thread stopped unexpectedly");
+        vec![project::CheckIssue {
+            message: "The project check stopped unexpectedly. Try checking again.".into(),
+            fix: None,
+        }]
+    })
+}
+
+/// Points the DAP server at `port`, restarting it when it is already running.
+fn switch_dap_port(port: String) -> Result<(), String> {
+    let Ok(mut server) = crate::dap_server().lock() else {
+        return Ok(());
+    };
+    server.port = port;
+    if !server.stop() {
+        return Ok(());
+    }
+    let tx = crate::display_buffer()
+        .lock()
+        .map_err(|_| "DAP server restart: log buffer unavailable".to_string())?
+        .sender();
+    server.start(tx)?;
+    crate::log_info(format!(
+        "probe-rs DAP Server restarted on {}:{}",
+        server.ip, server.port
+    ));
+    Ok(())
+}
+
+/// Reads the optional numeric fields of the project settings form.
+fn parse_override<T: std::str::FromStr>(value: &str, field: &str) -> Result<Option<T>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("{field}: invalid number {value}"))
+}
+
+/// Branch or tag typed into the Generate form, `None` when left blank.
+fn template_ref(value: &str, is_tag: bool) -> Option<TemplateRef> {
+    let value = non_empty(value)?;
+    Some(if is_tag {
+        TemplateRef::Tag(value)
+    } else {
+        TemplateRef::Branch(value)
+    })
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
 }
 
 #[component]
@@ -19,24 +434,147 @@ pub fn App() -> Element {
     let mut project_name = use_signal(|| "myproject".to_string());
     let mut template_ref_is_tag = use_signal(|| false); // false = branch, true = tag
     let mut template_ref_value = use_signal(|| String::new());
+    // The ref the placeholder form was loaded for, updated when editing ends
+    let mut placeholder_ref_value = use_signal(|| String::new());
     let mut vscode_open_enabled = use_signal(|| true);
-    let mut dap_port = use_signal(|| "50001".to_string());
-    let mut dap_ip = use_signal(|| "127.0.0.1".to_string());
+    let mut dap_port = use_signal(|| settings::load().dap_port.to_string());
+    let mut dap_ip = use_signal(|| settings::load().dap_ip);
+    let mut default_probe = use_signal(|| settings::load().probe);
     let mut dap_running = use_signal(|| false);
     let mut logs = use_signal(Vec::<String>::new);
-    let mut docker_status = use_signal(|| "Docker: ?".to_string());
+    let mut docker_status = use_signal(docker::DockerStatus::default);
+    let mut dev_containers = use_signal(Vec::<docker::DevContainer>::new);
+    let mut cargo_running = use_signal(|| Option::<(String, CargoTask)>::None);
+    let mut prepare_status = use_signal(|| Option::<String>::None);
+    let mut disk_usage = use_signal(|| Option::<docker::DiskUsage>::None);
+    let mut disk_busy = use_signal(|| false);
+    let mut prepare_images = use_signal(|| settings::load().prepare_images);
+    let mut cargo_result = use_signal(|| Option::<(String, CargoTask, CargoOutcome)>::None);
+    let mut engine_name = use_signal(|| engine::EngineKind::RancherDesktop.label());
     let mut last_error = use_signal(|| Option::<String>::None);
     let mut docker_prompt_dismissed = use_signal(|| false);
     let mut history = use_signal(settings::load_history);
     let mut show_history = use_signal(|| false);
+    let mut history_query = use_signal(String::new);
+    let mut history_max = use_signal(settings::load_history_max);
     let mut show_splash = use_signal(settings::should_show_splash);
     let mut show_reset_confirm = use_signal(|| false);
+    let mut container_confirm = use_signal(|| None::<(String, DevContainerAction)>);
+    let mut show_settings = use_signal(|| false);
+    let mut show_doctor = use_signal(|| false);
+    let mut doctor_report = use_signal(|| Option::<doctor::DoctorReport>::None);
+    let mut doctor_running = use_signal(|| false);
+    let mut udev_rules_path = use_signal(|| Option::<std::path::PathBuf>::None);
+    let mut editor = use_signal(|| settings::load().editor);
+    let mut editor_command = use_signal(|| settings::load().editor_command);
+    let mut open_in_container = use_signal(|| settings::load().open_in_container);
+    let installed_editors = use_signal(editor::detect_installed);
+    let mut editor_check = use_signal(|| Option::<editor::ExtensionCheck>::None);
+    let mut checking_editor = use_signal(|| false);
+    let mut log_level = use_signal(|| settings::load().log_level);
+    let mut splash_enabled = use_signal(|| settings::load().show_splash);
+    let mut container_engine = use_signal(|| settings::load().container_engine);
+    let detected_engine = use_signal(engine::detect);
+    let installed_engines = use_signal(|| {
+        engine::EngineKind::ALL
+            .into_iter()
+            .filter(|kind| {
+                *kind != engine::EngineKind::Auto && engine::engine(*kind).is_installed()
+            })
+            .collect::<Vec<_>>()
+    });
     let mut show_version_opts = use_signal(|| false);
+    let mut template_catalog = use_signal(templates::catalog);
+    let mut selected_template = use_signal(settings::load_selected_template);
+    let mut show_template_manager = use_signal(|| false);
+    let mut new_template_name = use_signal(|| String::new());
+    let mut new_template_location = use_signal(|| String::new());
+    let mut new_template_ref_is_tag = use_signal(|| false);
+    let mut new_template_ref = use_signal(|| String::new());
+    let mut new_template_description = use_signal(|| String::new());
+    let mut template_placeholders = use_signal(Vec::<templates::Placeholder>::new);
+    let mut placeholder_values = use_signal(HashMap::<String, String>::new);
+    let mut loading_template_options = use_signal(|| false);
+    let mut template_options_request = use_signal(|| 0u64);
+    let mut offline_mode = use_signal(settings::load_offline_mode);
+    let mut cached_templates = use_signal(template_cache::load_manifest);
+    let mut caching_template = use_signal(|| false);
+    let mut generate_cancel = use_signal(|| Option::<CancellationToken>::None);
     let mut probe_name = use_signal(|| String::new());
     let mut chip_name = use_signal(|| String::new());
     let mut chip_cores = use_signal(|| String::new());
     let mut chip_voltage = use_signal(|| String::new());
+    let mut detected_chip = use_signal(|| Option::<String>::None);
     let mut detecting = use_signal(|| false);
+    let mut detected_probe = use_signal(|| Option::<cmd::ProbeInfo>::None);
+    let mut probe_firmware_info =
+        use_signal(|| Option::<Result<probe_firmware::ProbeFirmware, String>>::None);
+    let mut firmware_step = use_signal(|| Option::<FirmwareStep>::None);
+    let mut firmware_resume_dap = use_signal(|| false);
+    let mut project_check = use_signal(|| Option::<(String, Vec<project::CheckIssue>)>::None);
+    let mut project_settings_path = use_signal(|| Option::<String>::None);
+    let mut override_port = use_signal(String::new);
+    let mut override_probe = use_signal(String::new);
+    let mut override_chip = use_signal(String::new);
+    let mut override_speed = use_signal(String::new);
+    let mut override_connect_under_reset = use_signal(|| false);
+    let mut probe_choices = use_signal(Vec::<String>::new);
+
+    // launch.json values derived from the DAP port and the last detected chip
+    let launch_params = move || project::LaunchParams {
+        port: dap_port
+            .read()
+            .parse()
+            .unwrap_or(parameter::DEFAULT_DAP_PORT),
+        chip: detected_chip.read().clone(),
+        probe: default_probe.read().clone(),
+        speed_khz: None,
+        connect_under_reset: false,
+    };
+    // Refreshes the signals backed by settings.json after an import or reset
+    let mut reload_settings = move || {
+        let s = settings::load();
+        history.set(settings::load_history());
+        history_max.set(s.history_max);
+        template_catalog.set(templates::catalog());
+        selected_template.set(s.selected_template.clone());
+        offline_mode.set(s.offline_mode);
+        default_probe.set(s.probe.clone());
+        editor.set(s.editor);
+        editor_command.set(s.editor_command.clone());
+        open_in_container.set(s.open_in_container);
+        log_level.set(s.log_level);
+        splash_enabled.set(s.show_splash);
+        container_engine.set(s.container_engine);
+        prepare_images.set(s.prepare_images);
+        dap_ip.set(s.dap_ip.clone());
+        dap_port.set(s.dap_port.to_string());
+        if let Ok(mut server) = crate::dap_server().lock() {
+            // A running server keeps its address until it is restarted
+            server.ip = s.dap_ip;
+            server.port = s.dap_port.to_string();
+        }
+    };
+    // Global values with the project's History overrides applied
+    let project_launch_params =
+        move |path: &str| launch_params().with_overrides(&settings::load_overrides(path));
+    let current_template =
+        move || templates::find(&template_catalog.read(), &selected_template.read());
+    // Checks compare against the project's port override, then the running DAP
+    // server, or the configured port when stopped
+    let check_context = move |path: &str| {
+        let overrides = settings::load_overrides(path);
+        project::CheckContext {
+            port: overrides.dap_port.unwrap_or_else(|| {
+                crate::dap_server()
+                    .lock()
+                    .ok()
+                    .and_then(|server| server.running_port())
+                    .unwrap_or_else(|| launch_params().port)
+            }),
+            chip: overrides.chip.or_else(|| detected_chip.read().clone()),
+        }
+    };
 
     // Action dispatcher coroutine — single place for all side-effects
     let actions = use_coroutine(move |mut rx: UnboundedReceiver<AppAction>| async move {
@@ -77,34 +615,136 @@ pub fn App() -> Element {
                 AppAction::StartDocker => {
                     let (tx, rx) = tokio::sync::oneshot::channel();
                     std::thread::spawn(move || {
-                        let _ = tx.send(cmd::start_rd());
+                        let engine = engine::selected();
+                        let _ = tx.send((engine.name(), engine.start()));
                     });
                     match rx.await {
-                        Ok(Ok(_)) => crate::log_info("Rancher Desktop started"),
-                        Ok(Err(e)) => {
-                            crate::log_error(format!("Rancher Desktop start failed: {}", e));
+                        Ok((name, Ok(_))) => crate::log_info(format!("{} started", name)),
+                        Ok((name, Err(e))) => {
+                            crate::log_error(format!("{} start failed: {}", name, e));
+                            last_error.set(Some(e));
+                        }
+                        Err(_) => {
+                            crate::log_error("Container engine start: channel closed");
+                        }
+                    }
+                }
+                AppAction::StopDocker => {
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    std::thread::spawn(move || {
+                        let engine = engine::selected();
+                        let _ = tx.send((engine.name(), engine.stop()));
+                    });
+                    match rx.await {
+                        Ok((name, Ok(_))) => {
+                            crate::log_info(format!("{} stopped", name));
+                            // Stopping on purpose is not a reason to offer a restart
+                            docker_prompt_dismissed.set(true);
+                        }
+                        Ok((name, Err(e))) => {
+                            crate::log_error(format!("{} stop failed: {}", name, e));
                             last_error.set(Some(e));
                         }
                         Err(_) => {
-                            crate::log_error("Rancher Desktop start: channel closed");
+                            crate::log_error("Container engine stop: channel closed");
+                        }
+                    }
+                }
+                AppAction::LoadDiskUsage => {
+                    disk_busy.set(true);
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(docker::disk_usage());
+                    });
+                    match rx.await {
+                        Ok(Ok(usage)) => disk_usage.set(Some(usage)),
+                        Ok(Err(e)) => {
+                            crate::log_error(format!("Docker disk usage failed: {}", e));
+                            last_error.set(Some(e));
+                        }
+                        Err(_) => crate::log_error("Docker disk usage: channel closed"),
+                    }
+                    disk_busy.set(false);
+                }
+                AppAction::Prune(kind) => {
+                    disk_busy.set(true);
+                    let entries = history.read().clone();
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(
+                            run_prune(kind, entries)
+                                .map(|freed| (freed, docker::disk_usage().ok())),
+                        );
+                    });
+                    match rx.await {
+                        Ok(Ok((freed, usage))) => {
+                            crate::log_info(format!(
+                                "{} pruned, {} freed",
+                                kind.label(),
+                                helpers::format_bytes(freed)
+                            ));
+                            if usage.is_some() {
+                                disk_usage.set(usage);
+                            }
                         }
+                        Ok(Err(e)) => {
+                            crate::log_error(format!("Prune {} failed: {}", kind.label(), e));
+                            last_error.set(Some(e));
+                        }
+                        Err(_) => crate::log_error("Prune: channel closed"),
                     }
+                    disk_busy.set(false);
                 }
                 AppAction::OpenProject(path) => {
                     if std::path::Path::new(&path).exists() {
-                        // Start Rancher Desktop in background (non-blocking)
-                        std::thread::spawn(|| {
-                            let _ = cmd::start_rd();
-                        });
-                        crate::log_info(format!("Visual Studio Code opened: {}", path));
-                        let path_clone = path.clone();
-                        let (tx, rx) = tokio::sync::oneshot::channel();
-                        std::thread::spawn(move || {
-                            let _ = tx.send(cmd::open_vscode(&path_clone));
-                        });
-                        if let Ok(Err(e)) = rx.await {
-                            crate::log_error(format!("Visual Studio Code failed to open: {}", e));
+                        let overrides = settings::load_overrides(&path);
+                        // The override moves the server only; the global port setting stays,
+                        // and a project without one gets the server back on it
+                        let port = overrides
+                            .dap_port
+                            .map(|port| port.to_string())
+                            .unwrap_or_else(|| dap_port.read().clone());
+                        let server_port = crate::dap_server()
+                            .lock()
+                            .ok()
+                            .map(|server| server.port.clone());
+                        if server_port.is_some_and(|server_port| server_port != port) {
+                            if let Err(e) = switch_dap_port(port) {
+                                crate::log_error(e.clone());
+                                last_error.set(Some(e));
+                            }
+                            if let Ok(server) = crate::dap_server().lock() {
+                                dap_running.set(server.running_port().is_some());
+                            }
+                        }
+                        if !overrides.is_empty() {
+                            let params = project_launch_params(&path);
+                            let project_path = path.clone();
+                            let (tx, rx) = tokio::sync::oneshot::channel();
+                            std::thread::spawn(move || {
+                                let _ = tx.send(project::write_launch_json(
+                                    std::path::Path::new(&project_path),
+                                    &params,
+                                ));
+                            });
+                            match rx.await {
+                                Ok(Ok(_)) => {
+                                    crate::log_info(format!("Project settings applied: {}", path))
+                                }
+                                Ok(Err(e)) => {
+                                    crate::log_error(format!("Project settings: {:#}", e))
+                                }
+                                Err(_) => crate::log_error("Project settings: channel closed"),
+                            }
                         }
+                        let chip = detected_chip.read().clone();
+                        history.set(settings::update_history(&path, |entry| {
+                            entry.last_opened = settings::history_timestamp();
+                            if chip.is_some() {
+                                entry.chip = chip;
+                            }
+                        }));
+                        open_in_editor(path).await;
                     } else {
                         crate::log_error(format!("Project not found: {}", path));
                         last_error.set(Some(format!("Project not found: {}", path)));
@@ -122,17 +762,22 @@ pub fn App() -> Element {
                     chip_name.set(String::new());
                     chip_cores.set(String::new());
                     chip_voltage.set(String::new());
+                    detected_chip.set(None);
+                    detected_probe.set(None);
+                    probe_firmware_info.set(None);
                     let (tx, rx) = tokio::sync::oneshot::channel();
                     std::thread::spawn(move || {
                         let _ = tx.send(cmd::detect_target());
                     });
                     match rx.await {
                         Ok(Ok(info)) => {
+                            detected_probe.set(Some(info.probe.clone()));
                             probe_name.set(format!(
                                 "{} ({})",
                                 info.probe.identifier, info.probe.probe_type
                             ));
                             chip_name.set(info.chip_name.clone());
+                            detected_chip.set(Some(info.chip_name.clone()));
                             if !info.cores.is_empty() {
                                 if info.cores.iter().all(|c| c == &info.cores[0]) {
                                     chip_cores.set(format!(
@@ -154,11 +799,15 @@ pub fn App() -> Element {
                         }
                         Ok(Err(e)) => {
                             let probes = cmd::list_probes();
-                            if probes.is_empty() {
+                            if probes.is_empty() && !udev::denied_devices().is_empty() {
+                                probe_name.set("No USB permission".to_string());
+                                last_error.set(Some(e.clone()));
+                            } else if probes.is_empty() {
                                 probe_name.set("No probe found".to_string());
                             } else {
                                 let p = &probes[0];
                                 probe_name.set(format!("{} ({})", p.identifier, p.probe_type));
+                                detected_probe.set(Some(p.clone()));
                                 chip_name.set("Detection failed".to_string());
                             }
                             crate::log_error(format!("Device detection: {e}"));
@@ -167,132 +816,1020 @@ pub fn App() -> Element {
                             probe_name.set("Detection failed".to_string());
                         }
                     }
+                    let baker_link = detected_probe
+                        .read()
+                        .clone()
+                        .filter(probe_firmware::is_baker_link);
+                    if let Some(probe) = baker_link {
+                        let (tx, rx) = tokio::sync::oneshot::channel();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(probe_firmware::read(&probe));
+                        });
+                        if let Ok(result) = rx.await {
+                            match &result {
+                                Ok(firmware) => crate::log_info(format!(
+                                    "Baker link probe firmware {} ({})",
+                                    firmware.version,
+                                    firmware.capabilities()
+                                )),
+                                Err(e) => crate::log_error(format!("Probe firmware: {}", e)),
+                            }
+                            probe_firmware_info.set(Some(result));
+                        }
+                    }
                     detecting.set(false);
                 }
-            }
-        }
-    });
-
-    // Auto-dismiss splash after 3 seconds (re-triggers on reset)
-    use_effect(move || {
-        if *show_splash.read() {
-            settings::mark_splash_shown();
-            spawn(async move {
-                tokio::time::sleep(Duration::from_millis(3000)).await;
-                show_splash.set(false);
-            });
-        }
-    });
-
-    // Poll logs every 300ms
-    use_future(move || async move {
-        loop {
-            tokio::time::sleep(Duration::from_millis(300)).await;
-            if let Ok(mut buffer) = crate::display_buffer().lock() {
-                buffer.channel_recv();
-                let latest = buffer.buffer.clone();
-                if latest != *logs.read() {
-                    logs.set(latest);
-                }
-            }
-        }
-    });
-
-    // Docker status auto-polling every 5 seconds
-    use_future(move || async move {
-        loop {
-            let (tx, rx) = tokio::sync::oneshot::channel();
-            std::thread::spawn(move || {
-                let _ = tx.send(cmd::is_docker_running());
-            });
-            if let Ok(result) = rx.await {
-                let new_status = match result {
-                    Ok(true) => "Docker: On".to_string(),
-                    Ok(false) => "Docker: Off".to_string(),
-                    Err(_) => "Docker: ?".to_string(),
-                };
-                if *docker_status.read() != new_status {
-                    if new_status.contains("On") {
-                        docker_prompt_dismissed.set(false);
+                AppAction::WriteLaunchConfig(path) => {
+                    let params = project_launch_params(&path);
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(project::write_launch_json(
+                            std::path::Path::new(&path),
+                            &params,
+                        ));
+                    });
+                    match rx.await {
+                        Ok(Ok(file)) => {
+                            crate::log_info(format!("launch.json written: {}", file.display()));
+                        }
+                        Ok(Err(e)) => {
+                            crate::log_error(format!("launch.json update failed: {:#}", e));
+                            last_error.set(Some(format!("launch.json update failed: {}", e)));
+                        }
+                        Err(_) => {
+                            crate::log_error("launch.json update: channel closed");
+                        }
                     }
-                    docker_status.set(new_status);
                 }
-            }
-            tokio::time::sleep(Duration::from_secs(5)).await;
-        }
-    });
-
-    rsx! {
-        document::Title { "{parameter::APP_NAME}" }
-        document::Stylesheet { href: "/assets/tailwind.css" }
-
-        div { class: "app-shell",
-
-            // ===== TOP BAR =====
-            div { class: "flex items-center gap-4 py-2 px-5 border-b border-bkl-border bg-bkl-sidebar shrink-0",
-
-                div { class: "flex items-center gap-2.5",
-                    button {
-                        class: "brand-icon brand-icon-button",
-                        title: "Reset all settings",
-                        onclick: move |_| show_reset_confirm.set(true),
-                        "B"
-                    }
-                    span { class: "text-sm font-extrabold text-bkl-text leading-none tracking-tight",
-                        "{parameter::APP_NAME}"
-                    }
-                    span { class: "text-[10px] text-bkl-text-faint ml-1.5",
-                        "{parameter::build_version_label()}"
+                AppAction::CheckProject(path) => {
+                    let issues = run_project_check(path.clone(), check_context(&path)).await;
+                    if issues.is_empty() {
+                        crate::log_info(format!("Project check passed: {}", path));
+                    } else {
+                        crate::log_info(format!(
+                            "Project check found {} problem(s): {}",
+                            issues.len(),
+                            path
+                        ));
                     }
-
-                    div { class: "flex items-center gap-1.5 ml-4 pl-4 border-l border-bkl-border",
-                        span { class: "size-2 rounded-full inline-block shrink-0 {helpers::docker_dot_class(&docker_status.read())}" }
-                        span { class: "text-xs text-bkl-text-muted", "{docker_status}" }
+                    project_check.set(Some((path, issues)));
+                }
+                AppAction::FixProject(path, fix) => {
+                    let params = project_launch_params(&path);
+                    let fix_path = path.clone();
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(project::apply_fix(
+                            std::path::Path::new(&fix_path),
+                            &fix,
+                            &params,
+                        ));
+                    });
+                    match rx.await {
+                        Ok(Ok(())) => {
+                            crate::log_info(format!("Project configuration updated: {}", path))
+                        }
+                        Ok(Err(e)) => {
+                            crate::log_error(format!("Project fix failed: {:#}", e));
+                            last_error.set(Some(format!("Project fix failed: {}", e)));
+                        }
+                        Err(_) => crate::log_error("Project fix: channel closed"),
                     }
+                    let issues = run_project_check(path.clone(), check_context(&path)).await;
+                    project_check.set(Some((path, issues)));
                 }
-
-                div { class: "flex-1" }
-
-                div { class: "flex items-center gap-2",
-
-                    // History dropdown
-                    div { class: "relative",
-                        button {
-                            class: "btn-chip",
-                            onclick: move |_| {
-                                let current = *show_history.read();
-                                show_history.set(!current);
-                            },
-                            "History"
+                AppAction::LoadTemplateOptions(template, template_ref, offline) => {
+                    // A newer request makes the result of this one stale
+                    let request = *template_options_request.peek() + 1;
+                    template_options_request.set(request);
+                    loading_template_options.set(true);
+                    template_placeholders.set(Vec::new());
+                    // Loading can clone the template, so keep the other actions running
+                    spawn(async move {
+                        let (tx, rx) = tokio::sync::oneshot::channel();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(templates::load_placeholders(
+                                &template,
+                                template_ref.as_ref(),
+                                offline,
+                            ));
+                        });
+                        let result = rx.await;
+                        if *template_options_request.peek() != request {
+                            return;
                         }
-                        if *show_history.read() {
-                            div { class: "dropdown-menu",
-                                if history.read().is_empty() {
-                                    div { class: "p-4 text-center text-xs text-bkl-text-faint",
-                                        "No history yet"
-                                    }
-                                }
-                                for entry in history.read().iter() {
-                                    {
-                                        let entry_path = entry.path.clone();
-                                        let entry_name = entry.name.clone();
-                                        rsx! {
-                                            button {
-                                                class: "dropdown-item",
-                                                onclick: move |_| {
-                                                    show_history.set(false);
-                                                    actions.send(AppAction::OpenProject(entry_path.clone()));
-                                                },
-                                                div { class: "text-[13px] font-semibold text-bkl-text", "{entry_name}" }
-                                                div { class: "text-[11px] text-bkl-text-faint mt-0.5 break-all", "{entry_path}" }
-                                            }
-                                        }
-                                    }
-                                }
+                        match result {
+                            Ok(Ok(placeholders)) => {
+                                placeholder_values.set(
+                                    placeholders
+                                        .iter()
+                                        .map(|p| (p.name.clone(), p.default.clone()))
+                                        .collect(),
+                                );
+                                template_placeholders.set(placeholders);
+                            }
+                            Ok(Err(e)) => {
+                                crate::log_error(format!("Template options unavailable: {}", e));
+                            }
+                            Err(_) => {
+                                crate::log_error("Template options: channel closed");
                             }
                         }
+                        loading_template_options.set(false);
+                    });
+                }
+                AppAction::CacheTemplate(template) => {
+                    if *caching_template.read() {
+                        continue;
                     }
-
+                    caching_template.set(true);
+                    crate::log_info(format!("Caching template {}...", template.name));
+                    // Caching clones the template, so keep the other actions running
+                    spawn(async move {
+                        let name = template.name.clone();
+                        let (tx, rx) = tokio::sync::oneshot::channel();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(template_cache::cache_template(&template));
+                        });
+                        match rx.await {
+                            Ok(Ok(entry)) => {
+                                crate::log_info(format!(
+                                    "Template {} cached at {}",
+                                    entry.name,
+                                    entry.revision_label()
+                                ));
+                            }
+                            Ok(Err(e)) => {
+                                crate::log_error(format!("Template cache failed: {}", e));
+                                // Only report it while that template is still selected
+                                if *selected_template.peek() == name {
+                                    last_error.set(Some(e));
+                                }
+                            }
+                            Err(_) => {
+                                crate::log_error("Template cache: channel closed");
+                            }
+                        }
+                        cached_templates.set(template_cache::load_manifest());
+                        caching_template.set(false);
+                    });
+                }
+                AppAction::ImportTemplateArchive(archive) => {
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(template_cache::import_archive(&archive));
+                    });
+                    match rx.await {
+                        Ok(Ok(count)) => {
+                            crate::log_info(format!("Imported {} cached template(s)", count));
+                        }
+                        Ok(Err(e)) => {
+                            crate::log_error(format!("Template archive import failed: {}", e));
+                            last_error.set(Some(e));
+                        }
+                        Err(_) => {
+                            crate::log_error("Template archive import: channel closed");
+                        }
+                    }
+                    cached_templates.set(template_cache::load_manifest());
+                }
+                AppAction::GenerateProject(request, open_after) => {
+                    let project_dir = request.project_dir().to_string_lossy().to_string();
+                    if request.project_dir().exists() {
+                        crate::log_info(format!("Project {} already exists", project_dir));
+                        if open_after {
+                            open_in_editor(project_dir).await;
+                        }
+                        continue;
+                    }
+                    let cancel = CancellationToken::new();
+                    generate_cancel.set(Some(cancel.clone()));
+                    // Clone and render in a background task so the other actions keep running
+                    spawn(async move {
+                        let name = request.name.clone();
+                        let template = request.template.name.clone();
+                        let template_ref = request
+                            .template_ref
+                            .as_ref()
+                            .or(request.template.default_ref.as_ref())
+                            .map(|r| r.name().to_string());
+                        let chip = request.launch.chip.clone();
+                        let cancelled = cancel.clone();
+                        let (tx, rx) = tokio::sync::oneshot::channel();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(cmd::generate_project(&request, &cancel));
+                        });
+                        match rx.await {
+                            Ok(Ok(dir)) => {
+                                let project_dir = dir.to_string_lossy().to_string();
+                                crate::log_info(format!("Project {} generated", project_dir));
+                                history.set(settings::add_history(settings::HistoryEntry {
+                                    name,
+                                    path: project_dir.clone(),
+                                    devcontainer: project::has_devcontainer(&dir),
+                                    template: Some(template),
+                                    template_ref,
+                                    chip,
+                                    ..Default::default()
+                                }));
+                                if open_after {
+                                    open_in_editor(project_dir).await;
+                                }
+                            }
+                            Ok(Err(e)) => {
+                                crate::log_error(format!("Project generation failed: {:#}", e));
+                                if !cancelled.is_cancelled() {
+                                    last_error
+                                        .set(Some(format!("Project generation failed: {}", e)));
+                                }
+                            }
+                            Err(_) => {
+                                crate::log_error("Project generation: channel closed");
+                            }
+                        }
+                        generate_cancel.set(None);
+                    });
+                }
+                AppAction::ImportProject(dir) => match project::import_project(&dir) {
+                    Ok(entry) => {
+                        crate::log_info(format!(
+                            "Project {} added to history ({})",
+                            entry.name,
+                            if entry.devcontainer {
+                                "Dev Container config found"
+                            } else {
+                                "no Dev Container config"
+                            }
+                        ));
+                        history.set(settings::add_history(entry));
+                    }
+                    Err(e) => {
+                        crate::log_error(format!("Add existing project failed: {}", e));
+                        last_error.set(Some(e));
+                    }
+                },
+                AppAction::ExportTemplateCache(dest) => {
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    let dest_clone = dest.clone();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(template_cache::export_archive(&dest_clone));
+                    });
+                    match rx.await {
+                        Ok(Ok(())) => {
+                            crate::log_info(format!("Template cache exported: {}", dest.display()));
+                        }
+                        Ok(Err(e)) => {
+                            crate::log_error(format!("Template cache export failed: {}", e));
+                            last_error.set(Some(e));
+                        }
+                        Err(_) => {
+                            crate::log_error("Template cache export: channel closed");
+                        }
+                    }
+                }
+                AppAction::RunCargo(path, task) => {
+                    if cargo_running.read().is_some() {
+                        crate::log_error("A cargo command is already running");
+                        continue;
+                    }
+                    cargo_running.set(Some((path.clone(), task)));
+                    // Builds take minutes, so keep the other actions running meanwhile
+                    spawn(async move {
+                        let (tx, rx) = tokio::sync::oneshot::channel();
+                        let task_path = path.clone();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(run_cargo_task(&task_path, task));
+                        });
+                        match rx.await {
+                            Ok(Ok(outcome)) => {
+                                let summary = format!(
+                                    "cargo {} {}: {} error(s), {} warning(s)",
+                                    task.command(),
+                                    if outcome.success {
+                                        "finished"
+                                    } else {
+                                        "failed"
+                                    },
+                                    outcome.count("error"),
+                                    outcome.count("warning"),
+                                );
+                                if outcome.success {
+                                    crate::log_info(summary);
+                                } else {
+                                    crate::log_error(summary);
+                                }
+                                if let Some(elf) = &outcome.elf {
+                                    crate::log_info(format!("ELF: {}", elf));
+                                }
+                                cargo_result.set(Some((path, task, outcome)));
+                            }
+                            Ok(Err(e)) => {
+                                crate::log_error(format!("cargo {} failed: {}", task.command(), e));
+                                last_error.set(Some(e));
+                            }
+                            Err(_) => crate::log_error("cargo: channel closed"),
+                        }
+                        cargo_running.set(None);
+                    });
+                }
+                AppAction::PrepareEnvironments => {
+                    if prepare_status.read().is_some() {
+                        continue;
+                    }
+                    let targets: Vec<PrepareTarget> = history
+                        .read()
+                        .iter()
+                        .filter(|entry| entry.devcontainer)
+                        .map(|entry| PrepareTarget {
+                            name: entry.name.clone(),
+                            dir: std::path::PathBuf::from(&entry.path),
+                            build: true,
+                        })
+                        .collect();
+                    if targets.is_empty() {
+                        continue;
+                    }
+                    prepare_status.set(Some("Preparing Dev Container images".to_string()));
+                    spawn(prepare_environments(targets, prepare_status));
+                }
+                AppAction::PrepareTemplate(template) => {
+                    if prepare_status.read().is_some() {
+                        crate::log_error("Dev Container images are already being prepared");
+                        continue;
+                    }
+                    prepare_status.set(Some(format!("Preparing {}", template.name)));
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    let lookup = template.clone();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(template_dir(&lookup));
+                    });
+                    match rx.await {
+                        Ok(Ok(dir)) => {
+                            cached_templates.set(template_cache::load_manifest());
+                            let target = PrepareTarget {
+                                name: template.name.clone(),
+                                dir,
+                                build: false,
+                            };
+                            spawn(prepare_environments(vec![target], prepare_status));
+                        }
+                        Ok(Err(e)) => {
+                            crate::log_error(format!("Preparing {} failed: {}", template.name, e));
+                            last_error.set(Some(e));
+                            prepare_status.set(None);
+                        }
+                        Err(_) => {
+                            crate::log_error("Prepare environment: channel closed");
+                            prepare_status.set(None);
+                        }
+                    }
+                }
+                AppAction::OpenDiagnostic(diagnostic) => {
+                    let Some(file) = &diagnostic.path else {
+                        continue;
+                    };
+                    let s = settings::load();
+                    if let Err(e) = cmd::open_file_at(
+                        s.editor,
+                        &s.editor_command,
+                        file,
+                        diagnostic.line,
+                        diagnostic.column,
+                    ) {
+                        crate::log_error(format!("{} failed to open: {}", s.editor.label(), e));
+                    }
+                }
+                AppAction::DevContainer(path, action) => {
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    let action_path = path.clone();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(run_dev_container_action(&action_path, action));
+                    });
+                    match rx.await {
+                        Ok(Ok(message)) => crate::log_info(message),
+                        Ok(Err(e)) => {
+                            crate::log_error(format!("Dev Container {}: {}", action.label(), e));
+                            last_error.set(Some(e));
+                        }
+                        Err(_) => crate::log_error("Dev Container: channel closed"),
+                    }
+                    dev_containers.set(load_dev_containers().await);
+                }
+                AppAction::ImportSettings(src) => match settings::import_settings(&src) {
+                    Ok(_) => {
+                        reload_settings();
+                        crate::log_info(format!("Settings imported: {}", src.display()));
+                    }
+                    Err(e) => {
+                        crate::log_error(format!("Settings import failed: {}", e));
+                        last_error.set(Some(e));
+                    }
+                },
+                AppAction::RunDoctor => {
+                    if *doctor_running.read() {
+                        continue;
+                    }
+                    doctor_running.set(true);
+                    // Network and probe checks can take a while, so keep the other actions running
+                    spawn(async move {
+                        let (tx, rx) = tokio::sync::oneshot::channel();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(doctor::run());
+                        });
+                        match rx.await {
+                            Ok(report) => {
+                                crate::log_info(format!(
+                                    "Doctor: {} passed, {} warnings, {} failed",
+                                    report.count(doctor::CheckStatus::Pass),
+                                    report.count(doctor::CheckStatus::Warn),
+                                    report.count(doctor::CheckStatus::Fail)
+                                ));
+                                doctor_report.set(Some(report));
+                            }
+                            Err(_) => crate::log_error("Doctor: channel closed"),
+                        }
+                        doctor_running.set(false);
+                    });
+                }
+                AppAction::ExportDoctorReport(dest, json) => {
+                    let Some(report) = doctor_report.read().clone() else {
+                        continue;
+                    };
+                    let text = if json {
+                        report.to_json()
+                    } else {
+                        report.to_markdown()
+                    };
+                    match std::fs::write(&dest, text) {
+                        Ok(()) => {
+                            crate::log_info(format!("Doctor report exported: {}", dest.display()))
+                        }
+                        Err(e) => {
+                            crate::log_error(format!("Doctor report export failed: {}", e));
+                            last_error.set(Some(e.to_string()));
+                        }
+                    }
+                }
+                AppAction::GenerateUdevRules => match udev::write_rules() {
+                    Ok(path) => {
+                        crate::log_info(format!("udev rules written: {}", path.display()));
+                        udev_rules_path.set(Some(path));
+                    }
+                    Err(e) => {
+                        crate::log_error(format!("udev rules: {}", e));
+                        last_error.set(Some(e));
+                    }
+                },
+                AppAction::InstallUdevRules(path) => {
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(cmd::install_udev_rules(&path));
+                    });
+                    match rx.await {
+                        Ok(Ok(())) => {
+                            crate::log_info(format!(
+                                "udev rules installed to {}/{}. Replug the board and run Doctor again.",
+                                udev::RULES_DIR,
+                                udev::RULES_FILE
+                            ));
+                        }
+                        Ok(Err(e)) => {
+                            crate::log_error(e.clone());
+                            last_error.set(Some(e));
+                        }
+                        Err(_) => crate::log_error("udev rules: channel closed"),
+                    }
+                }
+                AppAction::WaitForBootloader => {
+                    // The bootloader replaces the probe, so the DAP server has to let go of it
+                    if let Ok(mut server) = crate::dap_server().lock() {
+                        if server.stop() {
+                            dap_running.set(false);
+                            firmware_resume_dap.set(true);
+                            crate::log_info("probe-rs DAP Server stopped for the firmware update");
+                        }
+                    }
+                    firmware_step.set(Some(FirmwareStep::Bootloader { waiting: true }));
+                    spawn(async move {
+                        let (tx, rx) = tokio::sync::oneshot::channel();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(probe_firmware::wait_for(
+                                Duration::from_secs(120),
+                                probe_firmware::find_bootloader_drive,
+                            ));
+                        });
+                        let drive = rx.await.ok().flatten();
+                        // Closing the dialog cancels the flow
+                        if firmware_step.read().is_none() {
+                            return;
+                        }
+                        match drive {
+                            Some(drive) => {
+                                crate::log_info(format!("Bootloader drive found: {}", drive.display()));
+                                firmware_step.set(Some(FirmwareStep::Flash { drive }));
+                            }
+                            None => firmware_step.set(Some(FirmwareStep::Failed(
+                                "The RPI-RP2 drive did not appear. Hold BOOTSEL while plugging the board in, and check that the drive is mounted.".to_string(),
+                            ))),
+                        }
+                    });
+                }
+                AppAction::FlashFirmware(file, drive) => {
+                    firmware_step.set(Some(FirmwareStep::Verify));
+                    spawn(async move {
+                        let (tx, rx) = tokio::sync::oneshot::channel();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(update_probe_firmware(&file, &drive));
+                        });
+                        let result = rx
+                            .await
+                            .unwrap_or_else(|_| Err("Firmware update: channel closed".to_string()));
+                        match result {
+                            Ok((probe, firmware)) => {
+                                crate::log_info(format!(
+                                    "Probe firmware updated to {}",
+                                    firmware.version
+                                ));
+                                probe_name
+                                    .set(format!("{} ({})", probe.identifier, probe.probe_type));
+                                detected_probe.set(Some(probe));
+                                firmware_step.set(Some(FirmwareStep::Done(format!(
+                                    "{} is running firmware {}.",
+                                    firmware.product, firmware.version
+                                ))));
+                                probe_firmware_info.set(Some(Ok(firmware)));
+                            }
+                            Err(e) => {
+                                crate::log_error(format!("Probe firmware update failed: {}", e));
+                                firmware_step.set(Some(FirmwareStep::Failed(e)));
+                            }
+                        }
+                        if *firmware_resume_dap.read() {
+                            firmware_resume_dap.set(false);
+                            match resume_dap_server() {
+                                Ok(()) => dap_running.set(true),
+                                Err(e) => {
+                                    crate::log_error(e.clone());
+                                    last_error.set(Some(e));
+                                }
+                            }
+                        }
+                    });
+                }
+                AppAction::ExportSettings(dest, include_history) => {
+                    match settings::export_settings(&dest, include_history) {
+                        Ok(()) => crate::log_info(format!("Settings exported: {}", dest.display())),
+                        Err(e) => {
+                            crate::log_error(format!("Settings export failed: {}", e));
+                            last_error.set(Some(e));
+                        }
+                    }
+                }
+                AppAction::CheckEditorSetup => {
+                    checking_editor.set(true);
+                    let current = *editor.read();
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(editor::check_extensions(current));
+                    });
+                    if let Ok(check) = rx.await {
+                        if let Some(e) = &check.error {
+                            crate::log_error(format!("Editor check: {}", e));
+                        }
+                        for (_, name, installed) in &check.extensions {
+                            if !installed && check.error.is_none() {
+                                crate::log_info(format!(
+                                    "{} extension missing: {} (Settings \u{2192} Editor)",
+                                    check.editor.label(),
+                                    name
+                                ));
+                            }
+                        }
+                        editor_check.set(Some(check));
+                    }
+                    checking_editor.set(false);
+                }
+                AppAction::InstallExtensions(cli, ids) => {
+                    checking_editor.set(true);
+                    for id in ids {
+                        crate::log_info(format!("Installing extension {}...", id));
+                        let (tx, rx) = tokio::sync::oneshot::channel();
+                        let cli = cli.clone();
+                        let install_id = id.clone();
+                        std::thread::spawn(move || {
+                            let _ = tx.send(cmd::install_editor_extension(&cli, &install_id));
+                        });
+                        match rx.await {
+                            Ok(Ok(())) => crate::log_info(format!("Extension installed: {}", id)),
+                            Ok(Err(e)) => {
+                                crate::log_error(e.clone());
+                                last_error.set(Some(e));
+                            }
+                            Err(_) => crate::log_error("Extension install: channel closed"),
+                        }
+                    }
+                    let current = *editor.read();
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(editor::check_extensions(current));
+                    });
+                    if let Ok(check) = rx.await {
+                        editor_check.set(Some(check));
+                    }
+                    checking_editor.set(false);
+                }
+                AppAction::EditProjectSettings(path) => {
+                    let overrides = settings::load_overrides(&path);
+                    override_port.set(
+                        overrides
+                            .dap_port
+                            .map(|p| p.to_string())
+                            .unwrap_or_default(),
+                    );
+                    override_probe.set(overrides.probe.unwrap_or_default());
+                    override_chip.set(overrides.chip.unwrap_or_default());
+                    override_speed.set(
+                        overrides
+                            .speed_khz
+                            .map(|s| s.to_string())
+                            .unwrap_or_default(),
+                    );
+                    override_connect_under_reset.set(overrides.connect_under_reset);
+                    project_settings_path.set(Some(path));
+                    let (tx, rx) = tokio::sync::oneshot::channel();
+                    std::thread::spawn(move || {
+                        let _ = tx.send(cmd::list_probes());
+                    });
+                    if let Ok(probes) = rx.await {
+                        probe_choices.set(probes.iter().map(cmd::ProbeInfo::selector).collect());
+                    }
+                }
+            }
+        }
+    });
+
+    // Reload the placeholder form whenever the selected template changes
+    use_effect(move || {
+        actions.send(AppAction::LoadTemplateOptions(
+            current_template(),
+            template_ref(&placeholder_ref_value.read(), *template_ref_is_tag.peek()),
+            *offline_mode.read(),
+        ));
+    });
+
+    // Seed an empty template cache from the archive shipped with the app
+    use_future(move || async move {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(template_cache::seed_from_bundle());
+        });
+        match rx.await {
+            Ok(Ok(0)) | Err(_) => {}
+            Ok(Ok(count)) => {
+                crate::log_info(format!("Template cache seeded with {} template(s)", count));
+                cached_templates.set(template_cache::load_manifest());
+            }
+            Ok(Err(e)) => crate::log_error(format!("Bundled template archive: {}", e)),
+        }
+    });
+
+    // Report missing extensions once at startup, unless the editor to check
+    // is not installed at all
+    use_future(move || async move {
+        if editor::extension_host(*editor.peek()).is_installed() {
+            actions.send(AppAction::CheckEditorSetup);
+        }
+    });
+
+    // Auto-dismiss splash after 3 seconds (re-triggers on reset)
+    use_effect(move || {
+        if *show_splash.read() {
+            settings::mark_splash_shown();
+            spawn(async move {
+                tokio::time::sleep(Duration::from_millis(3000)).await;
+                show_splash.set(false);
+            });
+        }
+    });
+
+    // Poll logs every 300ms
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            if let Ok(mut buffer) = crate::display_buffer().lock() {
+                buffer.channel_recv();
+                let latest = buffer.buffer.clone();
+                if latest != *logs.read() {
+                    logs.set(latest);
+                }
+            }
+        }
+    });
+
+    // Docker Engine API status polling every 5 seconds
+    use_future(move || async move {
+        loop {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            std::thread::spawn(move || {
+                let _ = tx.send((engine::selected().name(), docker::status()));
+            });
+            if let Ok((name, new_status)) = rx.await {
+                if *engine_name.read() != name {
+                    engine_name.set(name);
+                }
+                let containers = if new_status.is_up() {
+                    load_dev_containers().await
+                } else {
+                    Vec::new()
+                };
+                if *dev_containers.read() != containers {
+                    dev_containers.set(containers);
+                }
+                if *docker_status.read() != new_status {
+                    if new_status.is_up() && !docker_status.read().is_up() {
+                        docker_prompt_dismissed.set(false);
+                        if *prepare_images.read() {
+                            actions.send(AppAction::PrepareEnvironments);
+                        }
+                    }
+                    docker_status.set(new_status);
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    });
+
+    rsx! {
+        document::Title { "{parameter::APP_NAME}" }
+        document::Stylesheet { href: "/assets/tailwind.css" }
+
+        div { class: "app-shell",
+
+            // ===== TOP BAR =====
+            div { class: "flex items-center gap-4 py-2 px-5 border-b border-bkl-border bg-bkl-sidebar shrink-0",
+
+                div { class: "flex items-center gap-2.5",
+                    div { class: "brand-icon", "B" }
+                    span { class: "text-sm font-extrabold text-bkl-text leading-none tracking-tight",
+                        "{parameter::APP_NAME}"
+                    }
+                    span { class: "text-[10px] text-bkl-text-faint ml-1.5",
+                        "{parameter::build_version_label()}"
+                    }
+
+                    div {
+                        class: "flex items-center gap-1.5 ml-4 pl-4 border-l border-bkl-border",
+                        title: "{docker_status.read().details()}",
+                        span { class: "size-2 rounded-full inline-block shrink-0 {helpers::docker_dot_class(&docker_status.read())}" }
+                        span { class: "text-xs text-bkl-text-muted",
+                            {
+                                let status = docker_status.read();
+                                match &status.version {
+                                    Some(version) => format!(
+                                        "{engine_name} {version} \u{00b7} {} running",
+                                        status.containers_running,
+                                    ),
+                                    None if status.is_down() => format!("{engine_name}: Off"),
+                                    None => format!("{engine_name}: ?"),
+                                }
+                            }
+                        }
+                    }
+
+                    if let Some(status) = prepare_status.read().clone() {
+                        div { class: "prepare-status", title: "Preparing Dev Container images",
+                            span { class: "size-2 rounded-full inline-block shrink-0 {helpers::dap_dot_class(true)}" }
+                            span { "{status}" }
+                        }
+                    }
+                }
+
+                div { class: "flex-1" }
+
+                div { class: "flex items-center gap-2",
+
+                    button {
+                        class: if *show_doctor.read() { "btn-chip btn-chip-active" } else { "btn-chip" },
+                        title: "Check the tools this app depends on",
+                        onclick: move |_| {
+                            let current = *show_doctor.read();
+                            show_doctor.set(!current);
+                            show_settings.set(false);
+                            if !current && doctor_report.read().is_none() {
+                                actions.send(AppAction::RunDoctor);
+                            }
+                        },
+                        "Doctor"
+                    }
+
+                    button {
+                        class: if *show_settings.read() { "btn-chip btn-chip-active" } else { "btn-chip" },
+                        onclick: move |_| {
+                            let current = *show_settings.read();
+                            show_settings.set(!current);
+                            show_doctor.set(false);
+                        },
+                        "Settings"
+                    }
+
+                    // History dropdown
+                    div { class: "relative",
+                        button {
+                            class: "btn-chip",
+                            onclick: move |_| {
+                                let current = *show_history.read();
+                                show_history.set(!current);
+                            },
+                            "History"
+                        }
+                        if *show_history.read() {
+                            div { class: "dropdown-menu",
+                                button {
+                                    class: "dropdown-item text-xs font-semibold text-bkl-orange",
+                                    onclick: move |_| {
+                                        show_history.set(false);
+                                        if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                                            actions.send(AppAction::ImportProject(dir));
+                                        }
+                                    },
+                                    "+ Add existing project\u{2026}"
+                                }
+                                div { class: "dropdown-search",
+                                    input {
+                                        class: "input text-xs",
+                                        r#type: "search",
+                                        placeholder: "Search name, path, template or chip",
+                                        value: "{history_query}",
+                                        oninput: move |e| history_query.set(e.value()),
+                                    }
+                                }
+                                if history.read().is_empty() {
+                                    div { class: "p-4 text-center text-xs text-bkl-text-faint",
+                                        "No history yet"
+                                    }
+                                }
+                                for entry in history.read().iter().filter(|e| e.matches(&history_query.read())) {
+                                    {
+                                        let entry_path = entry.path.clone();
+                                        let pin_path = entry.path.clone();
+                                        let entry_pinned = entry.pinned;
+                                        let entry_summary = entry.summary();
+                                        let launch_path = entry.path.clone();
+                                        let container_path = entry.path.clone();
+                                        let check_path = entry.path.clone();
+                                        let settings_path = entry.path.clone();
+                                        let entry_name = entry.name.clone();
+                                        let entry_devcontainer = entry.devcontainer;
+                                        let entry_dir = std::path::Path::new(&entry.path);
+                                        let container = project::find_dev_container(entry_dir, &dev_containers.read())
+                                            .cloned();
+                                        let container_state = if docker_status.read().is_up() {
+                                            project::dev_container_state(entry_dir, container.as_ref())
+                                        } else {
+                                            None
+                                        };
+                                        let container_actions: Vec<DevContainerAction> = match (&container, container_state) {
+                                            (_, None) => Vec::new(),
+                                            (None, Some(_)) => vec![DevContainerAction::Rebuild],
+                                            (Some(c), Some(_)) if c.is_running() => {
+                                                vec![DevContainerAction::Stop, DevContainerAction::Remove, DevContainerAction::Rebuild]
+                                            }
+                                            (Some(_), Some(_)) => {
+                                                vec![DevContainerAction::Start, DevContainerAction::Remove, DevContainerAction::Rebuild]
+                                            }
+                                        };
+                                        let container_title = container
+                                            .as_ref()
+                                            .map(|c| format!("{} ({})", c.name, c.status))
+                                            .unwrap_or_else(|| "No container yet".to_string());
+                                        rsx! {
+                                            div { class: "dropdown-row",
+                                                button {
+                                                    class: "dropdown-item",
+                                                    onclick: move |_| {
+                                                        show_history.set(false);
+                                                        actions.send(AppAction::OpenProject(entry_path.clone()));
+                                                    },
+                                                    div { class: "flex items-center gap-1.5",
+                                                        span { class: "text-[13px] font-semibold text-bkl-text", "{entry_name}" }
+                                                        if entry_devcontainer {
+                                                            span { class: "history-badge", "Dev Container" }
+                                                        }
+                                                        if let Some(state) = container_state {
+                                                            span {
+                                                                class: "container-badge {helpers::container_state_class(state)}",
+                                                                title: "{container_title}",
+                                                                "{state.label()}"
+                                                            }
+                                                        }
+                                                    }
+                                                    div { class: "text-[11px] text-bkl-text-faint mt-0.5 break-all", "{entry_path}" }
+                                                    if !entry_summary.is_empty() {
+                                                        div { class: "text-[11px] text-bkl-text-faint", "{entry_summary}" }
+                                                    }
+                                                }
+                                                div { class: "dropdown-row-actions",
+                                                    button {
+                                                        class: if entry_pinned { "btn-chip btn-chip-active" } else { "btn-chip" },
+                                                        title: if entry_pinned { "Unpin" } else { "Pin to the top" },
+                                                        onclick: move |_| {
+                                                            history.set(settings::update_history(&pin_path, |e| e.pinned = !entry_pinned));
+                                                        },
+                                                        if entry_pinned { "Pinned" } else { "Pin" }
+                                                    }
+                                                    button {
+                                                        class: "btn-chip",
+                                                        title: "Write probe-rs settings to .vscode/launch.json",
+                                                        onclick: move |_| {
+                                                            actions.send(AppAction::WriteLaunchConfig(launch_path.clone()));
+                                                        },
+                                                        "launch.json"
+                                                    }
+                                                    button {
+                                                        class: "btn-chip",
+                                                        title: "Check launch.json and devcontainer.json",
+                                                        onclick: move |_| {
+                                                            show_history.set(false);
+                                                            actions.send(AppAction::CheckProject(check_path.clone()));
+                                                        },
+                                                        "Check"
+                                                    }
+                                                    button {
+                                                        class: "btn-chip",
+                                                        title: "DAP port, probe and chip for this project",
+                                                        onclick: move |_| {
+                                                            show_history.set(false);
+                                                            actions.send(AppAction::EditProjectSettings(settings_path.clone()));
+                                                        },
+                                                        "Settings"
+                                                    }
+                                                    if entry_devcontainer {
+                                                        for task in CargoTask::ALL {
+                                                            {
+                                                                let task_path = container_path.clone();
+                                                                let running = cargo_running
+                                                                    .read()
+                                                                    .as_ref()
+                                                                    .is_some_and(|(p, t)| *p == task_path && *t == task);
+                                                                rsx! {
+                                                                    button {
+                                                                        class: "btn-chip",
+                                                                        title: "Run cargo {task.command()} in the Dev Container",
+                                                                        disabled: cargo_running.read().is_some(),
+                                                                        onclick: move |_| {
+                                                                            actions.send(AppAction::RunCargo(task_path.clone(), task));
+                                                                        },
+                                                                        if running { "{task.label()}\u{2026}" } else { "{task.label()}" }
+                                                                    }
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                    for action in container_actions {
+                                                        {
+                                                            let action_path = container_path.clone();
+                                                            rsx! {
+                                                                button {
+                                                                    class: "btn-chip",
+                                                                    title: "{action.label()} the Dev Container",
+                                                                    onclick: move |_| {
+                                                                        if action.warning().is_some() {
+                                                                            show_history.set(false);
+                                                                            container_confirm.set(Some((action_path.clone(), action)));
+                                                                        } else {
+                                                                            actions.send(AppAction::DevContainer(action_path.clone(), action));
+                                                                        }
+                                                                    },
+                                                                    "{action.label()}"
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                div { class: "dropdown-footer",
+                                    span { "Keep" }
+                                    input {
+                                        class: "input min-w-[64px] w-16 text-xs",
+                                        r#type: "number",
+                                        min: "1",
+                                        value: "{history_max}",
+                                        onchange: move |e| {
+                                            if let Ok(max) = e.value().parse::<usize>() {
+                                                history.set(settings::save_history_max(max));
+                                                history_max.set(settings::load_history_max());
+                                            }
+                                        },
+                                    }
+                                    span { "projects (pinned are never removed)" }
+                                }
+                            }
+                        }
+                    }
+
                     button {
                         class: "btn-chip",
                         onclick: move |_| {
@@ -308,359 +1845,1604 @@ pub fn App() -> Element {
             // ===== MAIN CONTENT =====
             main { class: "main-content flex-1 flex flex-col overflow-hidden py-5 px-6",
 
-                div { class: "grid grid-cols-2 gap-5 max-[860px]:grid-cols-1",
+                if *show_doctor.read() {
+                    // ---- Doctor ----
+                    div { class: "settings-view",
+                        div { class: "flex items-center justify-between mb-4",
+                            div {
+                                h2 { class: "m-0 text-[15px] font-bold text-bkl-text tracking-[0.02em]",
+                                    "Doctor"
+                                }
+                                p { class: "mt-0.5 text-xs text-bkl-text-muted",
+                                    "Checks the container engine, editor, probe, USB access, DAP port, template and config folder. Also available as `baker-link-env doctor`."
+                                }
+                            }
+                            div { class: "flex items-center gap-2",
+                                button {
+                                    class: "btn-chip",
+                                    disabled: *doctor_running.read(),
+                                    onclick: move |_| actions.send(AppAction::RunDoctor),
+                                    if *doctor_running.read() {
+                                        "Checking\u{2026}"
+                                    } else {
+                                        "Run again"
+                                    }
+                                }
+                                for (label , extension , json) in [("Export Markdown\u{2026}", "md", false), ("Export JSON\u{2026}", "json", true)] {
+                                    button {
+                                        class: "btn-chip",
+                                        disabled: doctor_report.read().is_none(),
+                                        onclick: move |_| {
+                                            if let Some(dest) = rfd::FileDialog::new()
+                                                .add_filter(if json { "JSON" } else { "Markdown" }, &[extension])
+                                                .set_file_name(format!("baker-link-env-doctor.{}", extension))
+                                                .save_file()
+                                            {
+                                                actions.send(AppAction::ExportDoctorReport(dest, json));
+                                            }
+                                        },
+                                        "{label}"
+                                    }
+                                }
+                                button {
+                                    class: "btn-chip",
+                                    onclick: move |_| show_doctor.set(false),
+                                    "Done"
+                                }
+                            }
+                        }
+
+                        section { class: "card settings-section",
+                            if let Some(report) = doctor_report.read().clone() {
+                                div { class: "text-[11px] text-bkl-text-faint mb-2",
+                                    "{report.app_version} \u{00b7} {report.os} \u{00b7} {report.generated_at}"
+                                }
+                                for check in report.checks {
+                                    div { class: "check-item",
+                                        span { class: helpers::check_status_class(check.status), "{check.status.label()}" }
+                                        div { class: "flex-1 min-w-0",
+                                            div { class: "text-[13px] font-semibold text-bkl-text", "{check.name}" }
+                                            div { class: "text-[11px] text-bkl-text-muted break-all", "{check.detail}" }
+                                            if let Some(hint) = check.hint {
+                                                div { class: "text-[11px] text-bkl-orange-light", "{hint}" }
+                                            }
+                                        }
+                                    }
+                                }
+                            } else {
+                                div { class: "text-[13px] text-bkl-text-muted", "Checking\u{2026}" }
+                            }
+                        }
+
+                        if cfg!(target_os = "linux") {
+                            section { class: "card settings-section",
+                                div { class: "settings-section-header",
+                                    h3 { "USB permissions" }
+                                    button {
+                                        class: "btn-chip",
+                                        title: "Write the rules file to the config folder",
+                                        onclick: move |_| actions.send(AppAction::GenerateUdevRules),
+                                        "Generate {udev::RULES_FILE}"
+                                    }
+                                }
+                                p { class: "mt-0 mb-2 text-xs text-bkl-text-muted",
+                                    "Without a udev rule, probe-rs cannot open the probe and it is reported as missing. The rules cover the Baker link probe, probe-rs' standard probe list and other CMSIS-DAP probes."
+                                }
+                                if let Some(path) = udev_rules_path.read().clone() {
+                                    for elevate in ["pkexec", "sudo"] {
+                                        {
+                                            let command = udev::install_command(elevate, &path);
+                                            let copy = command.clone();
+                                            rsx! {
+                                                div { class: "check-item",
+                                                    code { class: "flex-1 min-w-0 text-[11px] text-bkl-text break-all", "{command}" }
+                                                    button {
+                                                        class: "btn-chip",
+                                                        onclick: move |_| {
+                                                            if let Ok(mut cb) = arboard::Clipboard::new() {
+                                                                let _ = cb.set_text(copy.clone());
+                                                            }
+                                                        },
+                                                        "Copy"
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                    div { class: "flex items-center gap-2 mt-2",
+                                        button {
+                                            class: "btn-chip",
+                                            onclick: move |_| actions.send(AppAction::InstallUdevRules(path.clone())),
+                                            "Install with pkexec"
+                                        }
+                                        span { class: "text-[11px] text-bkl-text-faint", "Replug the board afterwards." }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                } else if *show_settings.read() {
+                    // ---- Settings ----
+                    div { class: "settings-view",
+                        div { class: "flex items-center justify-between mb-4",
+                            div {
+                                h2 { class: "m-0 text-[15px] font-bold text-bkl-text tracking-[0.02em]",
+                                    "Settings"
+                                }
+                                p { class: "mt-0.5 text-xs text-bkl-text-muted",
+                                    "Stored in {settings::config_dir().display()}. Each Reset restores the preset or built-in defaults."
+                                }
+                            }
+                            button {
+                                class: "btn-chip",
+                                onclick: move |_| show_settings.set(false),
+                                "Done"
+                            }
+                        }
 
-                    // ---- Create Project ----
-                    section { class: "card",
-                        h2 { class: "m-0 text-[15px] font-bold text-bkl-text tracking-[0.02em]",
-                            "Create Project"
+                        section { class: "card settings-section",
+                            div { class: "settings-section-header",
+                                h3 { "DAP Server" }
+                                button {
+                                    class: "btn-chip",
+                                    onclick: move |_| {
+                                        settings::reset_section(settings::SettingsSection::Dap);
+                                        reload_settings();
+                                    },
+                                    "Reset"
+                                }
+                            }
+                            div { class: "override-form",
+                                label { "IP" }
+                                input {
+                                    class: "input",
+                                    value: "{dap_ip}",
+                                    oninput: move |ev| dap_ip.set(ev.value()),
+                                    onchange: move |_| {
+                                        if let Ok(port) = dap_port.read().parse() {
+                                            settings::save_dap(&dap_ip.read(), port);
+                                            reload_settings();
+                                        }
+                                    },
+                                }
+                                label { "Port" }
+                                input {
+                                    class: "input",
+                                    r#type: "number",
+                                    value: "{dap_port}",
+                                    oninput: move |ev| dap_port.set(ev.value()),
+                                    onchange: move |_| {
+                                        if let Ok(port) = dap_port.read().parse() {
+                                            settings::save_dap(&dap_ip.read(), port);
+                                            reload_settings();
+                                        }
+                                    },
+                                }
+                                label { "Default probe" }
+                                input {
+                                    class: "input",
+                                    placeholder: "VID:PID[:serial] (blank = any)",
+                                    value: default_probe.read().clone().unwrap_or_default(),
+                                    onchange: move |ev| {
+                                        settings::save_probe(non_empty(&ev.value()));
+                                        default_probe.set(settings::load().probe);
+                                    },
+                                }
+                            }
+                        }
+
+                        section { class: "card settings-section",
+                            div { class: "settings-section-header",
+                                h3 { "Templates" }
+                                button {
+                                    class: "btn-chip",
+                                    onclick: move |_| {
+                                        settings::reset_section(settings::SettingsSection::Templates);
+                                        reload_settings();
+                                    },
+                                    "Reset"
+                                }
+                            }
+                            div { class: "flex items-center gap-3",
+                                span { class: "text-[13px] text-bkl-text-muted",
+                                    "{template_catalog.read().len()} templates ({settings::load().templates.len()} added by you)"
+                                }
+                                button {
+                                    class: "btn-chip",
+                                    onclick: move |_| show_template_manager.set(true),
+                                    "Manage\u{2026}"
+                                }
+                            }
+                            div { class: "flex items-center gap-2 mt-2",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: *offline_mode.read(),
+                                    onchange: move |ev| {
+                                        offline_mode.set(ev.checked());
+                                        settings::save_offline_mode(ev.checked());
+                                    },
+                                }
+                                span { class: "text-[13px] text-bkl-text-muted",
+                                    "Offline mode (use cached templates only)"
+                                }
+                            }
+                        }
+
+                        section { class: "card settings-section",
+                            div { class: "settings-section-header",
+                                h3 { "Editor" }
+                                button {
+                                    class: "btn-chip",
+                                    onclick: move |_| {
+                                        settings::reset_section(settings::SettingsSection::Editor);
+                                        reload_settings();
+                                    },
+                                    "Reset"
+                                }
+                            }
+                            select {
+                                class: "input",
+                                value: "{editor.read().id()}",
+                                onchange: move |ev| {
+                                    let choice = editor::Editor::from_id(&ev.value());
+                                    settings::save_editor(choice, &editor_command.read());
+                                    editor.set(choice);
+                                },
+                                for choice in editor::Editor::ALL {
+                                    option { value: "{choice.id()}",
+                                        if installed_editors.read().contains(&choice) {
+                                            "{choice.label()}"
+                                        } else {
+                                            "{choice.label()} (not found)"
+                                        }
+                                    }
+                                }
+                            }
+                            if editor.read().supports_dev_container() {
+                                div { class: "flex items-center gap-2 mt-2",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: *open_in_container.read(),
+                                        onchange: move |ev| {
+                                            settings::save_open_in_container(ev.checked());
+                                            open_in_container.set(ev.checked());
+                                        },
+                                    }
+                                    span { class: "text-[13px] text-bkl-text-muted",
+                                        "Open projects with .devcontainer/devcontainer.json directly in the Dev Container"
+                                    }
+                                }
+                            }
+                            if *editor.read() == editor::Editor::Custom {
+                                input {
+                                    class: "input mt-2",
+                                    placeholder: "e.g. subl {editor::PATH_PLACEHOLDER}",
+                                    value: "{editor_command}",
+                                    oninput: move |ev| editor_command.set(ev.value()),
+                                    onchange: move |_| settings::save_editor(*editor.read(), &editor_command.read()),
+                                }
+                                p { class: "mt-1 text-[11px] text-bkl-text-faint",
+                                    "{editor::PATH_PLACEHOLDER} is replaced with the project folder, or appended when missing."
+                                }
+                            }
+                            div { class: "flex items-center gap-2 mt-4",
+                                span { class: "flex-1 text-[13px] font-semibold text-bkl-text-muted",
+                                    "Extensions"
+                                }
+                                if let Some(check) = editor_check.read().clone() {
+                                    if let (Some(cli), false) = (check.cli.clone(), check.missing().is_empty()) {
+                                        button {
+                                            class: "btn-primary",
+                                            disabled: *checking_editor.read(),
+                                            onclick: move |_| {
+                                                actions.send(AppAction::InstallExtensions(cli.clone(), check.missing()));
+                                            },
+                                            "Install missing"
+                                        }
+                                    }
+                                }
+                                button {
+                                    class: "btn-chip",
+                                    disabled: *checking_editor.read(),
+                                    onclick: move |_| actions.send(AppAction::CheckEditorSetup),
+                                    if *checking_editor.read() { "Checking..." } else { "Check again" }
+                                }
+                            }
+                            if let Some(check) = editor_check.read().clone() {
+                                p { class: "mt-1 mb-2 text-[11px] text-bkl-text-faint break-all",
+                                    {
+                                        match &check.cli {
+                                            Some(cli) => format!("{} CLI: {}", check.editor.label(), cli.display()),
+                                            None => format!("{} CLI not found", check.editor.label()),
+                                        }
+                                    }
+                                }
+                                if let Some(error) = &check.error {
+                                    p { class: "m-0 mb-2 text-[12px] text-bkl-red", "{error}" }
+                                }
+                                for (id , name , installed) in check.extensions.iter().cloned() {
+                                    div { class: "check-item",
+                                        span { class: "flex-1 text-[13px] text-bkl-text-muted",
+                                            "{name} "
+                                            span { class: "text-[11px] text-bkl-text-faint", "{id}" }
+                                        }
+                                        if installed {
+                                            span { class: "text-[12px] text-bkl-green", "Installed" }
+                                        } else if let Some(cli) = check.cli.clone() {
+                                            button {
+                                                class: "btn-chip",
+                                                disabled: *checking_editor.read(),
+                                                onclick: move |_| {
+                                                    actions.send(AppAction::InstallExtensions(cli.clone(), vec![id.clone()]));
+                                                },
+                                                "Install"
+                                            }
+                                        } else {
+                                            span { class: "text-[12px] text-bkl-red", "Missing" }
+                                        }
+                                    }
+                                }
+                            }
                         }
-                        p { class: "mt-0.5 text-xs text-bkl-text-muted",
-                            "Generate a template-based project and open it in VS Code."
+
+                        section { class: "card settings-section",
+                            div { class: "settings-section-header",
+                                h3 { "Log" }
+                                button {
+                                    class: "btn-chip",
+                                    onclick: move |_| {
+                                        settings::reset_section(settings::SettingsSection::Log);
+                                        reload_settings();
+                                    },
+                                    "Reset"
+                                }
+                            }
+                            select {
+                                class: "input",
+                                value: "{log_level.read().id()}",
+                                onchange: move |ev| {
+                                    let level = logger::LogLevel::from_id(&ev.value());
+                                    settings::save_log_level(level);
+                                    log_level.set(level);
+                                },
+                                for level in logger::LogLevel::ALL {
+                                    option { value: "{level.id()}", "{level.label()}" }
+                                }
+                            }
                         }
 
-                        div { class: "mt-4",
-                            div { class: "flex flex-wrap items-center gap-2",
-                                label { class: "text-[13px] font-semibold text-bkl-text-muted",
-                                    "Project name"
+                        section { class: "card settings-section",
+                            div { class: "settings-section-header",
+                                h3 { "History" }
+                                button {
+                                    class: "btn-chip",
+                                    onclick: move |_| {
+                                        settings::reset_section(settings::SettingsSection::History);
+                                        reload_settings();
+                                    },
+                                    "Reset"
                                 }
+                            }
+                            div { class: "flex items-center gap-2 text-[13px] text-bkl-text-muted",
+                                span { "Keep" }
                                 input {
-                                    class: "input",
-                                    value: "{project_name}",
-                                    oninput: move |ev| project_name.set(ev.value()),
+                                    class: "input min-w-[64px] w-16",
+                                    r#type: "number",
+                                    min: "1",
+                                    value: "{history_max}",
+                                    onchange: move |e| {
+                                        if let Ok(max) = e.value().parse::<usize>() {
+                                            history.set(settings::save_history_max(max));
+                                            history_max.set(settings::load_history_max());
+                                        }
+                                    },
                                 }
+                                span { class: "flex-1", "projects" }
                                 button {
-                                    class: "btn-primary",
+                                    class: "btn-danger",
+                                    disabled: history.read().is_empty(),
                                     onclick: move |_| {
-                                        if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                                            let path_str = path.to_string_lossy().to_string();
-                                            let joined = std::path::Path::new(&path_str)
-                                                .join(project_name.read().as_str());
-                                            let joined_str = joined.to_string_lossy().to_string();
-                                            if !joined.exists() {
-                                                let ref_val = template_ref_value.read().trim().to_string();
-                                                let ref_opt = if ref_val.is_empty() { None } else { Some(ref_val) };
-                                                let (branch, tag) = if *template_ref_is_tag.read() {
-                                                    (None, ref_opt)
-                                                } else {
-                                                    (ref_opt, None)
-                                                };
-                                                match cmd::generate_project(
-                                                    project_name.read().as_str(),
-                                                    &path_str,
-                                                    branch,
-                                                    tag,
-                                                ) {
-                                                    Ok(_) => {
-                                                        crate::log_info(format!("Project {} generated", joined_str));
-                                                        let mut h = history.read().clone();
-                                                        if !h.iter().any(|e| e.path == joined_str) {
-                                                            if h.len() >= settings::HISTORY_MAX {
-                                                                h.remove(0);
-                                                            }
-                                                            h.push(settings::HistoryEntry {
-                                                                name: project_name.read().clone(),
-                                                                path: joined_str.clone(),
-                                                            });
-                                                            settings::save_history(&h);
-                                                            history.set(h);
-                                                        }
-                                                    }
-                                                    Err(e) => {
-                                                        crate::log_error(format!("Project generation failed: {}", e));
-                                                    }
-                                                }
-                                            } else {
-                                                crate::log_info(format!("Project {} already exists", joined_str));
-                                            }
-                                            if *vscode_open_enabled.read() {
-                                                let _ = cmd::start_rd();
-                                                crate::log_info(format!("Visual Studio Code opened: {}", joined_str));
-                                                if let Err(e) = cmd::open_vscode(&joined_str) {
-                                                    crate::log_error(
-                                                        format!("Visual Studio Code failed to open: {}", e),
-                                                    );
-                                                }
-                                            }
-                                        }
+                                        settings::save_history(&[]);
+                                        history.set(vec![]);
+                                        crate::log_info("Project history cleared");
                                     },
-                                    "Create"
+                                    "Clear history"
                                 }
                             }
+                        }
 
-                            div { class: "flex items-center gap-2 mt-2",
+                        section { class: "card settings-section",
+                            div { class: "settings-section-header",
+                                h3 { "Splash Screen" }
+                                button {
+                                    class: "btn-chip",
+                                    onclick: move |_| {
+                                        settings::reset_section(settings::SettingsSection::Splash);
+                                        reload_settings();
+                                    },
+                                    "Reset"
+                                }
+                            }
+                            div { class: "flex items-center gap-2",
                                 input {
                                     r#type: "checkbox",
-                                    checked: *show_version_opts.read(),
+                                    checked: *splash_enabled.read(),
                                     onchange: move |ev| {
-                                        show_version_opts.set(ev.checked());
+                                        settings::save_show_splash(ev.checked());
+                                        splash_enabled.set(ev.checked());
                                     },
                                 }
                                 span { class: "text-[13px] text-bkl-text-muted",
-                                    "Specify template version"
+                                    "Show the splash screen on the first start of the day"
+                                }
+                            }
+                        }
+
+                        section { class: "card settings-section",
+                            div { class: "settings-section-header",
+                                h3 { "Docker Engine" }
+                                button {
+                                    class: "btn-chip",
+                                    onclick: move |_| {
+                                        settings::reset_section(settings::SettingsSection::Engine);
+                                        reload_settings();
+                                    },
+                                    "Reset"
+                                }
+                            }
+                            select {
+                                class: "input",
+                                value: "{container_engine.read().id()}",
+                                onchange: move |ev| {
+                                    let kind = engine::EngineKind::from_id(&ev.value());
+                                    settings::save_container_engine(kind);
+                                    container_engine.set(kind);
+                                },
+                                for kind in engine::EngineKind::ALL {
+                                    option {
+                                        value: "{kind.id()}",
+                                        if kind == engine::EngineKind::Auto {
+                                            "{kind.label()} ({detected_engine.read().label()})"
+                                        } else if installed_engines.read().contains(&kind) {
+                                            "{kind.label()}"
+                                        } else {
+                                            "{kind.label()} (not found)"
+                                        }
+                                    }
+                                }
+                            }
+                            p { class: "mt-1 mb-0 text-[11px] text-bkl-text-faint",
+                                "Used for the status in the header, the Start button and opening projects."
+                            }
+                            div { class: "flex items-center gap-2 mt-2",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: *prepare_images.read(),
+                                    onchange: move |ev| {
+                                        settings::save_prepare_images(ev.checked());
+                                        prepare_images.set(ev.checked());
+                                    },
+                                }
+                                span { class: "flex-1 text-[13px] text-bkl-text-muted",
+                                    "Pull or build the Dev Container images of history projects when Docker starts"
+                                }
+                                button {
+                                    class: "btn-chip",
+                                    disabled: prepare_status.read().is_some() || !docker_status.read().is_up(),
+                                    onclick: move |_| actions.send(AppAction::PrepareEnvironments),
+                                    "Prepare now"
+                                }
+                            }
+                            div { class: "flex gap-2 mt-2",
+                                button {
+                                    class: "btn-chip",
+                                    disabled: docker_status.read().is_up(),
+                                    onclick: move |_| actions.send(AppAction::StartDocker),
+                                    "Start {engine_name}"
+                                }
+                                button {
+                                    class: "btn-chip",
+                                    disabled: !docker_status.read().is_up(),
+                                    onclick: move |_| actions.send(AppAction::StopDocker),
+                                    "Stop {engine_name}"
+                                }
+                            }
+                        }
+
+                        section { class: "card settings-section",
+                            div { class: "settings-section-header",
+                                h3 { "Docker Disk Usage" }
+                                button {
+                                    class: "btn-chip",
+                                    disabled: *disk_busy.read() || !docker_status.read().is_up(),
+                                    onclick: move |_| actions.send(AppAction::LoadDiskUsage),
+                                    if disk_usage.read().is_some() { "Refresh" } else { "Load" }
+                                }
+                            }
+                            if let Some(usage) = disk_usage.read().clone() {
+                                div { class: "disk-usage",
+                                    span { "Images" }
+                                    span { "{usage.images.len()}" }
+                                    span { "{helpers::format_bytes(usage.images_size())}" }
+                                    span { "Containers" }
+                                    span { "{usage.containers}" }
+                                    span { "{helpers::format_bytes(usage.containers_size)}" }
+                                    span { "Volumes" }
+                                    span { "{usage.volumes}" }
+                                    span { "{helpers::format_bytes(usage.volumes_size)}" }
+                                    span { "Build cache" }
+                                    span {}
+                                    span { "{helpers::format_bytes(usage.build_cache_size)}" }
+                                }
+                            }
+                            p { class: "mt-1 mb-2 text-[11px] text-bkl-text-faint",
+                                "Dev Containers and the images used by projects in History are never pruned."
+                            }
+                            div { class: "flex flex-wrap gap-2",
+                                for kind in docker::PruneKind::ALL {
+                                    button {
+                                        class: "btn-chip",
+                                        disabled: *disk_busy.read() || !docker_status.read().is_up(),
+                                        onclick: move |_| actions.send(AppAction::Prune(kind)),
+                                        "Prune {kind.label().to_lowercase()}"
+                                    }
                                 }
                             }
+                        }
+
+                        section { class: "card settings-section",
+                            div { class: "settings-section-header",
+                                h3 { "Backup" }
+                            }
+                            div { class: "flex flex-wrap gap-2",
+                                for (label , include_history) in [("Export settings\u{2026}", true), ("Export without history\u{2026}", false)] {
+                                    button {
+                                        class: "btn-chip",
+                                        onclick: move |_| {
+                                            if let Some(dest) = rfd::FileDialog::new()
+                                                .add_filter("JSON", &["json"])
+                                                .set_file_name("baker-link-env-settings.json")
+                                                .save_file()
+                                            {
+                                                actions.send(AppAction::ExportSettings(dest, include_history));
+                                            }
+                                        },
+                                        "{label}"
+                                    }
+                                }
+                                button {
+                                    class: "btn-chip",
+                                    onclick: move |_| {
+                                        if let Some(src) = rfd::FileDialog::new()
+                                            .add_filter("JSON", &["json"])
+                                            .pick_file()
+                                        {
+                                            actions.send(AppAction::ImportSettings(src));
+                                        }
+                                    },
+                                    "Import settings\u{2026}"
+                                }
+                                div { class: "flex-1" }
+                                button {
+                                    class: "btn-danger",
+                                    onclick: move |_| show_reset_confirm.set(true),
+                                    "Reset all settings"
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    div { class: "grid grid-cols-2 gap-5 max-[860px]:grid-cols-1",
+
+                        // ---- Create Project ----
+                        section { class: "card",
+                            h2 { class: "m-0 text-[15px] font-bold text-bkl-text tracking-[0.02em]",
+                                "Create Project"
+                            }
+                            p { class: "mt-0.5 text-xs text-bkl-text-muted",
+                                "Generate a template-based project and open it in {editor.read().label()}."
+                            }
 
-                            if *show_version_opts.read() {
+                            div { class: "mt-4",
                                 div { class: "flex flex-wrap items-center gap-2",
                                     label { class: "text-[13px] font-semibold text-bkl-text-muted",
-                                        "Version"
+                                        "Project name"
                                     }
-                                    div { class: "flex gap-1",
-                                        button {
-                                            class: if !*template_ref_is_tag.read() { "btn-chip btn-chip-active" } else { "btn-chip" },
-                                            onclick: move |_| template_ref_is_tag.set(false),
-                                            "Branch"
+                                    input {
+                                        class: "input",
+                                        value: "{project_name}",
+                                        oninput: move |ev| project_name.set(ev.value()),
+                                    }
+                                    button {
+                                        class: "btn-primary",
+                                        disabled: *loading_template_options.read() || generate_cancel.read().is_some()
+                                            || project::validate_crate_name(&project_name.read()).is_err(),
+                                        onclick: move |_| {
+                                            let defines = match templates::placeholder_defines(
+                                                &template_placeholders.read(),
+                                                &placeholder_values.read(),
+                                            ) {
+                                                Ok(defines) => defines,
+                                                Err(e) => {
+                                                    last_error.set(Some(e));
+                                                    return;
+                                                }
+                                            };
+                                            if let Some(destination) = rfd::FileDialog::new().pick_folder() {
+                                                if let Err(e) = project::validate_destination(&destination) {
+                                                    crate::log_error(e.clone());
+                                                    last_error.set(Some(e));
+                                                    return;
+                                                }
+                                                let template_ref = template_ref(
+                                                    &template_ref_value.read(),
+                                                    *template_ref_is_tag.read(),
+                                                );
+                                                let request = cmd::GenerateRequest {
+                                                    name: project_name.read().clone(),
+                                                    destination,
+                                                    template: current_template(),
+                                                    template_ref,
+                                                    defines,
+                                                    offline: *offline_mode.read(),
+                                                    launch: launch_params(),
+                                                };
+                                                actions.send(AppAction::GenerateProject(
+                                                    request,
+                                                    *vscode_open_enabled.read(),
+                                                ));
+                                            }
+                                        },
+                                        if generate_cancel.read().is_some() {
+                                            "Creating..."
+                                        } else {
+                                            "Create"
                                         }
+                                    }
+                                    if let Some(cancel) = generate_cancel.read().clone() {
                                         button {
-                                            class: if *template_ref_is_tag.read() { "btn-chip btn-chip-active" } else { "btn-chip" },
-                                            onclick: move |_| template_ref_is_tag.set(true),
-                                            "Tag"
+                                            class: "btn-danger",
+                                            onclick: move |_| {
+                                                cancel.cancel();
+                                                crate::log_info("Cancelling project generation...");
+                                            },
+                                            "Cancel"
                                         }
                                     }
-                                    input {
+                                }
+                                if let Err(name_error) = project::validate_crate_name(&project_name.read()) {
+                                    div { class: "flex flex-wrap items-center gap-2 mt-1.5",
+                                        span { class: "text-[11px] text-bkl-red", "{name_error}" }
+                                        {
+                                            let suggestion = project::suggest_crate_name(&project_name.read());
+                                            rsx! {
+                                                button {
+                                                    class: "btn-chip",
+                                                    onclick: move |_| project_name.set(suggestion.clone()),
+                                                    "Use \"{suggestion}\""
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+
+                                div { class: "flex flex-wrap items-center gap-2 mt-2",
+                                    label { class: "text-[13px] font-semibold text-bkl-text-muted",
+                                        "Template"
+                                    }
+                                    select {
                                         class: "input",
-                                        placeholder: if *template_ref_is_tag.read() { "e.g. v1.0.0 (blank = latest)" } else { "e.g. main (blank = HEAD)" },
-                                        value: "{template_ref_value}",
-                                        oninput: move |ev| template_ref_value.set(ev.value()),
+                                        onchange: move |ev| {
+                                            let name = ev.value();
+                                            settings::save_selected_template(&name);
+                                            selected_template.set(name);
+                                        },
+                                        for template in template_catalog.read().iter() {
+                                            option {
+                                                value: "{template.name}",
+                                                selected: template.name == current_template().name,
+                                                "{template.name}"
+                                            }
+                                        }
+                                    }
+                                    button {
+                                        class: "btn-chip",
+                                        onclick: move |_| show_template_manager.set(true),
+                                        "Manage"
+                                    }
+                                }
+                                if !current_template().description.is_empty() {
+                                    p { class: "mt-1 text-[11px] text-bkl-text-faint",
+                                        "{current_template().description}"
+                                    }
+                                }
+
+                                if *loading_template_options.read() {
+                                    p { class: "mt-2 text-[11px] text-bkl-text-faint",
+                                        "Loading template options..."
+                                    }
+                                }
+                                if !template_placeholders.read().is_empty() {
+                                    div { class: "placeholder-form",
+                                        for placeholder in template_placeholders.read().iter() {
+                                            {
+                                                let name = placeholder.name.clone();
+                                                let value = placeholder_values
+                                                    .read()
+                                                    .get(&name)
+                                                    .cloned()
+                                                    .unwrap_or_else(|| placeholder.default.clone());
+                                                let error = placeholder.validate(&value).err();
+                                                let field = match placeholder.kind.clone() {
+                                                    PlaceholderKind::Bool => rsx! {
+                                                        input {
+                                                            r#type: "checkbox",
+                                                            checked: value == "true",
+                                                            onchange: move |ev| {
+                                                                placeholder_values.write().insert(name.clone(), ev.checked().to_string());
+                                                            },
+                                                        }
+                                                    },
+                                                    PlaceholderKind::Choice(choices) => rsx! {
+                                                        select {
+                                                            class: "input",
+                                                            onchange: move |ev| {
+                                                                placeholder_values.write().insert(name.clone(), ev.value());
+                                                            },
+                                                            for choice in choices {
+                                                                option {
+                                                                    value: "{choice}",
+                                                                    selected: choice == value,
+                                                                    "{choice}"
+                                                                }
+                                                            }
+                                                        }
+                                                    },
+                                                    PlaceholderKind::Text => rsx! {
+                                                        input {
+                                                            class: "input",
+                                                            value: "{value}",
+                                                            oninput: move |ev| {
+                                                                placeholder_values.write().insert(name.clone(), ev.value());
+                                                            },
+                                                        }
+                                                    },
+                                                };
+                                                rsx! {
+                                                    div { class: "flex flex-wrap items-center gap-2",
+                                                        label {
+                                                            class: "text-[13px] font-semibold text-bkl-text-muted min-w-[120px]",
+                                                            title: "{placeholder.name}",
+                                                            "{placeholder.prompt}"
+                                                        }
+                                                        {field}
+                                                        if let Some(error) = error {
+                                                            span { class: "text-[11px] text-bkl-red", "{error}" }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+
+                                div { class: "flex items-center gap-2 mt-2",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: *show_version_opts.read(),
+                                        onchange: move |ev| {
+                                            show_version_opts.set(ev.checked());
+                                        },
+                                    }
+                                    span { class: "text-[13px] text-bkl-text-muted",
+                                        "Specify template version"
+                                    }
+                                }
+
+                                if *show_version_opts.read() {
+                                    div { class: "flex flex-wrap items-center gap-2",
+                                        label { class: "text-[13px] font-semibold text-bkl-text-muted",
+                                            "Version"
+                                        }
+                                        div { class: "flex gap-1",
+                                            button {
+                                                class: if !*template_ref_is_tag.read() { "btn-chip btn-chip-active" } else { "btn-chip" },
+                                                onclick: move |_| template_ref_is_tag.set(false),
+                                                "Branch"
+                                            }
+                                            button {
+                                                class: if *template_ref_is_tag.read() { "btn-chip btn-chip-active" } else { "btn-chip" },
+                                                onclick: move |_| template_ref_is_tag.set(true),
+                                                "Tag"
+                                            }
+                                        }
+                                        input {
+                                            class: "input",
+                                            placeholder: if *template_ref_is_tag.read() { "e.g. v1.0.0 (blank = latest)" } else { "e.g. main (blank = HEAD)" },
+                                            value: "{template_ref_value}",
+                                            oninput: move |ev| template_ref_value.set(ev.value()),
+                                            onchange: move |ev| placeholder_ref_value.set(ev.value()),
+                                        }
+                                    }
+                                }
+
+                                div { class: "flex items-center gap-2 mt-2",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: *vscode_open_enabled.read(),
+                                        onchange: move |ev| {
+                                            vscode_open_enabled.set(ev.checked());
+                                        },
+                                    }
+                                    span { class: "text-[13px] text-bkl-text-muted",
+                                        "Open in {editor.read().label()} after creation"
+                                    }
+                                }
+
+                                div { class: "flex items-center gap-2 mt-2",
+                                    input {
+                                        r#type: "checkbox",
+                                        checked: *offline_mode.read(),
+                                        onchange: move |ev| {
+                                            settings::save_offline_mode(ev.checked());
+                                            offline_mode.set(ev.checked());
+                                        },
+                                    }
+                                    span { class: "text-[13px] text-bkl-text-muted",
+                                        "Offline mode (use cached templates)"
+                                    }
+                                }
+
+                                if let Some(url) = current_template().web_url() {
+                                    a {
+                                        class: "template-link",
+                                        href: "{url}",
+                                        "View template repository"
                                     }
                                 }
                             }
+                        }
 
-                            div { class: "flex items-center gap-2 mt-2",
-                                input {
-                                    r#type: "checkbox",
-                                    checked: *vscode_open_enabled.read(),
-                                    onchange: move |ev| {
-                                        vscode_open_enabled.set(ev.checked());
-                                    },
+                        // ---- DAP Server ----
+                        section { class: "card",
+                            div { class: "flex items-center justify-between",
+                                div {
+                                    h2 { class: "m-0 text-[15px] font-bold text-bkl-text tracking-[0.02em]",
+                                        "probe-rs DAP Server"
+                                    }
+                                    p { class: "mt-0.5 text-xs text-bkl-text-muted",
+                                        "Launch a local DAP server for debugging."
+                                    }
+                                }
+                                div { class: "flex items-center gap-1.5 text-[11px] text-bkl-text-muted",
+                                    span { class: "size-2 rounded-full inline-block shrink-0 {helpers::dap_dot_class(*dap_running.read())}" }
+                                    span {
+                                        if *dap_running.read() {
+                                            "Running"
+                                        } else {
+                                            "Stopped"
+                                        }
+                                    }
+                                }
+                            }
+
+                            div { class: "mt-4",
+                                div { class: "mb-3.5 pb-3.5 border-b border-bkl-border",
+                                    div { class: "flex items-center justify-between",
+                                        span { class: "text-[13px] font-semibold text-bkl-text-muted",
+                                            "Connected Device"
+                                        }
+                                        div { class: "flex items-center gap-2",
+                                            if detected_probe.read().as_ref().is_some_and(probe_firmware::is_baker_link) {
+                                                button {
+                                                    class: "btn-chip",
+                                                    title: "Flash new firmware to the Baker link probe",
+                                                    onclick: move |_| firmware_step.set(Some(FirmwareStep::Bootloader { waiting: false })),
+                                                    "Update firmware\u{2026}"
+                                                }
+                                            }
+                                            button {
+                                                class: "btn-chip",
+                                                disabled: *detecting.read(),
+                                                onclick: move |_| actions.send(AppAction::DetectDevice),
+                                                if *detecting.read() {
+                                                    "Detecting..."
+                                                } else {
+                                                    "Detect"
+                                                }
+                                            }
+                                        }
+                                    }
+                                    if !probe_name.read().is_empty() {
+                                        div { class: "probe-info-grid",
+                                            div { class: "flex items-baseline gap-2 min-w-0",
+                                                span { class: "text-[10px] font-bold text-bkl-text-faint uppercase tracking-[0.04em] shrink-0",
+                                                    "Probe"
+                                                }
+                                                span { class: "text-xs text-bkl-text-muted font-mono overflow-hidden text-ellipsis whitespace-nowrap",
+                                                    "{probe_name}"
+                                                }
+                                            }
+                                            if !chip_name.read().is_empty() {
+                                                div { class: "flex items-baseline gap-2 min-w-0",
+                                                    span { class: "text-[10px] font-bold text-bkl-text-faint uppercase tracking-[0.04em] shrink-0",
+                                                        "Chip"
+                                                    }
+                                                    span { class: "text-xs font-mono font-semibold text-bkl-orange-light overflow-hidden text-ellipsis whitespace-nowrap",
+                                                        "{chip_name}"
+                                                    }
+                                                }
+                                            }
+                                            if !chip_cores.read().is_empty() {
+                                                div { class: "flex items-baseline gap-2 min-w-0",
+                                                    span { class: "text-[10px] font-bold text-bkl-text-faint uppercase tracking-[0.04em] shrink-0",
+                                                        "Core"
+                                                    }
+                                                    span { class: "text-xs text-bkl-text-muted font-mono overflow-hidden text-ellipsis whitespace-nowrap",
+                                                        "{chip_cores}"
+                                                    }
+                                                }
+                                            }
+                                            if !chip_voltage.read().is_empty() {
+                                                div { class: "flex items-baseline gap-2 min-w-0",
+                                                    span { class: "text-[10px] font-bold text-bkl-text-faint uppercase tracking-[0.04em] shrink-0",
+                                                        "Voltage"
+                                                    }
+                                                    span { class: "text-xs text-bkl-text-muted font-mono overflow-hidden text-ellipsis whitespace-nowrap",
+                                                        "{chip_voltage}"
+                                                    }
+                                                }
+                                            }
+                                            {
+                                                match probe_firmware_info.read().clone() {
+                                                    Some(Ok(firmware)) => {
+                                                        let max_clock = firmware
+                                                            .max_clock_khz
+                                                            .map(|khz| format!("{} MHz", khz / 1000))
+                                                            .unwrap_or_else(|| "unknown".to_string());
+                                                        let capabilities = firmware.capabilities();
+                                                        rsx! {
+                                                            div { class: "flex items-baseline gap-2 min-w-0",
+                                                                span { class: "text-[10px] font-bold text-bkl-text-faint uppercase tracking-[0.04em] shrink-0",
+                                                                    "Firmware"
+                                                                }
+                                                                span { class: "text-xs text-bkl-text-muted font-mono overflow-hidden text-ellipsis whitespace-nowrap",
+                                                                    "{firmware.version}"
+                                                                }
+                                                            }
+                                                            div { class: "flex items-baseline gap-2 min-w-0",
+                                                                span { class: "text-[10px] font-bold text-bkl-text-faint uppercase tracking-[0.04em] shrink-0",
+                                                                    "Features"
+                                                                }
+                                                                span { class: "text-xs text-bkl-text-muted font-mono overflow-hidden text-ellipsis whitespace-nowrap",
+                                                                    "{capabilities}"
+                                                                }
+                                                            }
+                                                            div { class: "flex items-baseline gap-2 min-w-0",
+                                                                span { class: "text-[10px] font-bold text-bkl-text-faint uppercase tracking-[0.04em] shrink-0",
+                                                                    "Max clock"
+                                                                }
+                                                                span { class: "text-xs text-bkl-text-muted font-mono overflow-hidden text-ellipsis whitespace-nowrap",
+                                                                    "{max_clock}"
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                    Some(Err(e)) => rsx! {
+                                                        div { class: "col-span-2 text-[11px] text-bkl-text-faint break-all",
+                                                            "Firmware: {e}"
+                                                        }
+                                                    },
+                                                    None => rsx! {},
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+
+                                div { class: "flex flex-wrap items-center gap-2",
+                                    label { class: "text-[13px] font-semibold text-bkl-text-muted",
+                                        "IP"
+                                    }
+                                    input {
+                                        class: "input min-w-[140px] w-[140px]",
+                                        value: "{dap_ip}",
+                                        oninput: move |ev| {
+                                            let value = ev.value();
+                                            dap_ip.set(value.clone());
+                                            if let Ok(mut server) = crate::dap_server().lock() {
+                                                server.ip = value;
+                                            }
+                                        },
+                                        onchange: move |_| {
+                                            if let Ok(port) = dap_port.read().parse() {
+                                                settings::save_dap(&dap_ip.read(), port);
+                                            }
+                                        },
+                                    }
+                                    label { class: "text-[13px] font-semibold text-bkl-text-muted",
+                                        "Port"
+                                    }
+                                    input {
+                                        class: "input min-w-[80px] w-[80px]",
+                                        value: "{dap_port}",
+                                        oninput: move |ev| {
+                                            let value = ev.value();
+                                            dap_port.set(value.clone());
+                                            if let Ok(mut server) = crate::dap_server().lock() {
+                                                server.port = value;
+                                            }
+                                        },
+                                        onchange: move |_| {
+                                            if let Ok(port) = dap_port.read().parse() {
+                                                settings::save_dap(&dap_ip.read(), port);
+                                            }
+                                        },
+                                    }
+                                    button {
+                                        class: "btn-primary",
+                                        disabled: *dap_running.read(),
+                                        onclick: move |_| actions.send(AppAction::StartDap),
+                                        "Run"
+                                    }
+                                    button {
+                                        class: "btn-danger",
+                                        disabled: !*dap_running.read(),
+                                        onclick: move |_| actions.send(AppAction::StopDap),
+                                        "Stop"
+                                    }
                                 }
-                                span { class: "text-[13px] text-bkl-text-muted",
-                                    "Open VS Code after creation"
+                                p { class: "mt-3 text-[11px] text-bkl-text-faint",
+                                    "New projects get a launch.json for {project::DOCKER_HOST}:{dap_port}. Use History \u{2192} launch.json to update existing ones."
                                 }
                             }
-
-                            a {
-                                class: "template-link",
-                                href: "{parameter::TEMPLATE_URL}",
-                                "View template repository"
-                            }
                         }
                     }
 
-                    // ---- DAP Server ----
-                    section { class: "card",
+                    // ---- Log ----
+                    section { class: "card mt-5 flex-1 flex flex-col min-h-0",
                         div { class: "flex items-center justify-between",
                             div {
                                 h2 { class: "m-0 text-[15px] font-bold text-bkl-text tracking-[0.02em]",
-                                    "probe-rs DAP Server"
+                                    "Log"
                                 }
                                 p { class: "mt-0.5 text-xs text-bkl-text-muted",
-                                    "Launch a local DAP server for debugging."
+                                    "Build and runtime output."
                                 }
                             }
-                            div { class: "flex items-center gap-1.5 text-[11px] text-bkl-text-muted",
-                                span { class: "size-2 rounded-full inline-block shrink-0 {helpers::dap_dot_class(*dap_running.read())}" }
-                                span {
-                                    if *dap_running.read() {
-                                        "Running"
-                                    } else {
-                                        "Stopped"
+                            button {
+                                class: "btn-chip",
+                                onclick: move |_| {
+                                    let text = logs.read().join("\n");
+                                    if let Ok(mut cb) = arboard::Clipboard::new() {
+                                        let _ = cb.set_text(text);
                                     }
-                                }
+                                },
+                                "Copy"
                             }
                         }
-
-                        div { class: "mt-4",
-                            div { class: "mb-3.5 pb-3.5 border-b border-bkl-border",
-                                div { class: "flex items-center justify-between",
-                                    span { class: "text-[13px] font-semibold text-bkl-text-muted",
-                                        "Connected Device"
+                        div { class: "log-viewer",
+                            for (idx , line) in logs.read().iter().enumerate().filter(|(_, line)| log_level.read().shows(line)) {
+                                div {
+                                    key: "{idx}",
+                                    class: "log-line {logger::log_level_class(line)}",
+                                    span { class: "text-bkl-text-faint shrink-0 select-all",
+                                        "{logger::extract_timestamp(line)}"
                                     }
-                                    button {
-                                        class: "btn-chip",
-                                        disabled: *detecting.read(),
-                                        onclick: move |_| actions.send(AppAction::DetectDevice),
-                                        if *detecting.read() {
-                                            "Detecting..."
-                                        } else {
-                                            "Detect"
-                                        }
+                                    span { class: "log-badge {logger::log_badge_class(line)}",
+                                        "{logger::extract_level(line)}"
                                     }
+                                    span { class: "break-all", "{logger::extract_message(line)}" }
                                 }
-                                if !probe_name.read().is_empty() {
-                                    div { class: "probe-info-grid",
-                                        div { class: "flex items-baseline gap-2 min-w-0",
-                                            span { class: "text-[10px] font-bold text-bkl-text-faint uppercase tracking-[0.04em] shrink-0",
-                                                "Probe"
-                                            }
-                                            span { class: "text-xs text-bkl-text-muted font-mono overflow-hidden text-ellipsis whitespace-nowrap",
-                                                "{probe_name}"
+                            }
+                        }
+                    }
+                }
+            }
+
+            // ---- Error toast ----
+            if let Some(err) = last_error.read().clone() {
+                div { class: "error-toast",
+                    div { class: "flex items-start justify-between gap-3",
+                        span { "[ERROR] {err}" }
+                        button {
+                            class: "shrink-0 text-bkl-text-faint text-lg leading-none cursor-pointer bg-transparent border-none p-0 hover:text-bkl-red",
+                            onclick: move |_| last_error.set(None),
+                            "\u{00d7}"
+                        }
+                    }
+                }
+            }
+
+            // ---- Docker startup prompt ----
+            if docker_status.read().is_down() && !*docker_prompt_dismissed.read()
+                && !*show_splash.read()
+            {
+                div { class: "modal-overlay",
+                    div { class: "modal",
+                        h3 { class: "m-0 mb-3 text-base font-bold text-bkl-text", "{engine_name}" }
+                        p { class: "m-0 mb-1 text-[13px] text-bkl-text-muted",
+                            "Docker is not running."
+                        }
+                        p { class: "m-0 mb-1 text-[13px] text-bkl-text-muted",
+                            "Start {engine_name} now?"
+                        }
+                        div { class: "flex gap-2 mt-5",
+                            button {
+                                class: "btn-primary",
+                                onclick: move |_| {
+                                    actions.send(AppAction::StartDocker);
+                                    docker_prompt_dismissed.set(true);
+                                },
+                                "Start"
+                            }
+                            button {
+                                class: "btn-chip",
+                                onclick: move |_| docker_prompt_dismissed.set(true),
+                                "Not now"
+                            }
+                        }
+                    }
+                }
+            }
+
+            // ---- Template manager ----
+            if *show_template_manager.read() {
+                div { class: "modal-overlay",
+                    div { class: "modal max-w-[560px]",
+                        h3 { class: "m-0 mb-3 text-base font-bold text-bkl-text", "Templates" }
+                        for template in template_catalog.read().iter() {
+                            {
+                                let remove_name = template.name.clone();
+                                let builtin = templates::is_builtin(&template.name);
+                                let is_git = matches!(template.source, templates::TemplateSource::Git(_));
+                                let cache_template = template.clone();
+                                let prepare_template = template.clone();
+                                let cached = cached_templates
+                                    .read()
+                                    .iter()
+                                    .find(|c| c.name == template.name)
+                                    .map(|c| format!("Cached {} on {}", c.revision_label(), c.cached_at));
+                                rsx! {
+                                    div { class: "check-item",
+                                        div { class: "flex-1 min-w-0",
+                                            div { class: "text-[13px] font-semibold text-bkl-text", "{template.name}" }
+                                            div { class: "text-[11px] text-bkl-text-faint break-all", "{template.location()}" }
+                                            if let Some(cached) = cached.clone() {
+                                                div { class: "text-[11px] text-bkl-green", "{cached}" }
                                             }
                                         }
-                                        if !chip_name.read().is_empty() {
-                                            div { class: "flex items-baseline gap-2 min-w-0",
-                                                span { class: "text-[10px] font-bold text-bkl-text-faint uppercase tracking-[0.04em] shrink-0",
-                                                    "Chip"
-                                                }
-                                                span { class: "text-xs font-mono font-semibold text-bkl-orange-light overflow-hidden text-ellipsis whitespace-nowrap",
-                                                    "{chip_name}"
+                                        if is_git {
+                                            button {
+                                                class: "btn-chip",
+                                                disabled: *caching_template.read(),
+                                                title: "Download this template for offline use",
+                                                onclick: move |_| actions.send(AppAction::CacheTemplate(cache_template.clone())),
+                                                if cached.is_some() {
+                                                    "Update cache"
+                                                } else {
+                                                    "Cache"
                                                 }
                                             }
                                         }
-                                        if !chip_cores.read().is_empty() {
-                                            div { class: "flex items-baseline gap-2 min-w-0",
-                                                span { class: "text-[10px] font-bold text-bkl-text-faint uppercase tracking-[0.04em] shrink-0",
-                                                    "Core"
-                                                }
-                                                span { class: "text-xs text-bkl-text-muted font-mono overflow-hidden text-ellipsis whitespace-nowrap",
-                                                    "{chip_cores}"
-                                                }
-                                            }
+                                        button {
+                                            class: "btn-chip",
+                                            disabled: prepare_status.read().is_some() || !docker_status.read().is_up(),
+                                            title: "Pull the Dev Container images this template needs",
+                                            onclick: move |_| actions.send(AppAction::PrepareTemplate(prepare_template.clone())),
+                                            "Prepare environment"
                                         }
-                                        if !chip_voltage.read().is_empty() {
-                                            div { class: "flex items-baseline gap-2 min-w-0",
-                                                span { class: "text-[10px] font-bold text-bkl-text-faint uppercase tracking-[0.04em] shrink-0",
-                                                    "Voltage"
-                                                }
-                                                span { class: "text-xs text-bkl-text-muted font-mono overflow-hidden text-ellipsis whitespace-nowrap",
-                                                    "{chip_voltage}"
-                                                }
+                                        if builtin {
+                                            span { class: "text-[11px] text-bkl-text-faint", "Built-in" }
+                                        } else {
+                                            button {
+                                                class: "btn-chip",
+                                                onclick: move |_| {
+                                                    templates::remove_user_template(&remove_name);
+                                                    template_catalog.set(templates::catalog());
+                                                },
+                                                "Remove"
                                             }
                                         }
                                     }
                                 }
                             }
+                        }
 
-                            div { class: "flex flex-wrap items-center gap-2",
-                                label { class: "text-[13px] font-semibold text-bkl-text-muted",
-                                    "IP"
-                                }
+                        div { class: "flex flex-col gap-2 mt-4",
+                            input {
+                                class: "input",
+                                placeholder: "Name",
+                                value: "{new_template_name}",
+                                oninput: move |ev| new_template_name.set(ev.value()),
+                            }
+                            div { class: "flex gap-2",
                                 input {
-                                    class: "input min-w-[140px] w-[140px]",
-                                    value: "{dap_ip}",
-                                    oninput: move |ev| {
-                                        let value = ev.value();
-                                        dap_ip.set(value.clone());
-                                        if let Ok(mut server) = crate::dap_server().lock() {
-                                            server.ip = value;
-                                        }
-                                    },
-                                }
-                                label { class: "text-[13px] font-semibold text-bkl-text-muted",
-                                    "Port"
+                                    class: "input flex-1",
+                                    placeholder: "Git URL or local folder",
+                                    value: "{new_template_location}",
+                                    oninput: move |ev| new_template_location.set(ev.value()),
                                 }
-                                input {
-                                    class: "input min-w-[80px] w-[80px]",
-                                    value: "{dap_port}",
-                                    oninput: move |ev| {
-                                        let value = ev.value();
-                                        dap_port.set(value.clone());
-                                        if let Ok(mut server) = crate::dap_server().lock() {
-                                            server.port = value;
+                                button {
+                                    class: "btn-chip",
+                                    onclick: move |_| {
+                                        if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                                            new_template_location.set(path.to_string_lossy().to_string());
                                         }
                                     },
+                                    "Browse"
                                 }
-                                button {
-                                    class: "btn-primary",
-                                    disabled: *dap_running.read(),
-                                    onclick: move |_| actions.send(AppAction::StartDap),
-                                    "Run"
+                            }
+                            div { class: "flex items-center gap-2",
+                                div { class: "flex gap-1",
+                                    button {
+                                        class: if !*new_template_ref_is_tag.read() { "btn-chip btn-chip-active" } else { "btn-chip" },
+                                        onclick: move |_| new_template_ref_is_tag.set(false),
+                                        "Branch"
+                                    }
+                                    button {
+                                        class: if *new_template_ref_is_tag.read() { "btn-chip btn-chip-active" } else { "btn-chip" },
+                                        onclick: move |_| new_template_ref_is_tag.set(true),
+                                        "Tag"
+                                    }
                                 }
-                                button {
-                                    class: "btn-danger",
-                                    disabled: !*dap_running.read(),
-                                    onclick: move |_| actions.send(AppAction::StopDap),
-                                    "Stop"
+                                input {
+                                    class: "input flex-1",
+                                    placeholder: "Default version (blank = HEAD)",
+                                    value: "{new_template_ref}",
+                                    oninput: move |ev| new_template_ref.set(ev.value()),
                                 }
                             }
-                            p { class: "mt-3 text-[11px] text-bkl-text-faint",
-                                "Docker path mapping: set pathMappings in launch.json (remoteRoot / localRoot)."
+                            input {
+                                class: "input",
+                                placeholder: "Description",
+                                value: "{new_template_description}",
+                                oninput: move |ev| new_template_description.set(ev.value()),
+                            }
+                        }
+
+                        div { class: "flex gap-2 mt-5",
+                            button {
+                                class: "btn-primary",
+                                onclick: move |_| {
+                                    let ref_val = new_template_ref.read().trim().to_string();
+                                    let default_ref = if ref_val.is_empty() {
+                                        None
+                                    } else if *new_template_ref_is_tag.read() {
+                                        Some(TemplateRef::Tag(ref_val))
+                                    } else {
+                                        Some(TemplateRef::Branch(ref_val))
+                                    };
+                                    let entry = TemplateEntry {
+                                        name: new_template_name.read().trim().to_string(),
+                                        source: templates::source_from_input(new_template_location.read().trim()),
+                                        default_ref,
+                                        description: new_template_description.read().trim().to_string(),
+                                    };
+                                    match templates::add_user_template(entry) {
+                                        Ok(()) => {
+                                            template_catalog.set(templates::catalog());
+                                            new_template_name.set(String::new());
+                                            new_template_location.set(String::new());
+                                            new_template_ref.set(String::new());
+                                            new_template_description.set(String::new());
+                                        }
+                                        Err(e) => last_error.set(Some(e)),
+                                    }
+                                },
+                                "Add"
+                            }
+                            button {
+                                class: "btn-chip",
+                                onclick: move |_| {
+                                    if let Some(archive) = rfd::FileDialog::new()
+                                        .add_filter("Template archive", &["gz", "tgz"])
+                                        .pick_file()
+                                    {
+                                        actions.send(AppAction::ImportTemplateArchive(archive));
+                                    }
+                                },
+                                "Import archive"
+                            }
+                            button {
+                                class: "btn-chip",
+                                disabled: cached_templates.read().is_empty(),
+                                onclick: move |_| {
+                                    if let Some(dest) = rfd::FileDialog::new()
+                                        .set_file_name("templates.tar.gz")
+                                        .save_file()
+                                    {
+                                        actions.send(AppAction::ExportTemplateCache(dest));
+                                    }
+                                },
+                                "Export cache"
+                            }
+                            div { class: "flex-1" }
+                            button {
+                                class: "btn-chip",
+                                onclick: move |_| show_template_manager.set(false),
+                                "Close"
                             }
                         }
                     }
                 }
+            }
 
-                // ---- Log ----
-                section { class: "card mt-5 flex-1 flex flex-col min-h-0",
-                    div { class: "flex items-center justify-between",
-                        div {
-                            h2 { class: "m-0 text-[15px] font-bold text-bkl-text tracking-[0.02em]",
-                                "Log"
-                            }
-                            p { class: "mt-0.5 text-xs text-bkl-text-muted",
-                                "Build and runtime output."
+            // ---- Project check results ----
+            if let Some((check_path, issues)) = project_check.read().clone() {
+                div { class: "modal-overlay",
+                    div { class: "modal max-w-[560px]",
+                        h3 { class: "m-0 mb-1 text-base font-bold text-bkl-text", "Project Check" }
+                        p { class: "m-0 mb-3 text-[11px] text-bkl-text-faint break-all", "{check_path}" }
+                        if issues.is_empty() {
+                            p { class: "m-0 text-[13px] text-bkl-green",
+                                "No problems found."
                             }
                         }
-                        button {
-                            class: "btn-chip",
-                            onclick: move |_| {
-                                let text = logs.read().join("\n");
-                                if let Ok(mut cb) = arboard::Clipboard::new() {
-                                    let _ = cb.set_text(text);
+                        for issue in issues.iter() {
+                            {
+                                let fix = issue.fix.clone();
+                                let fix_label = fix.as_ref().map(|f| f.label()).unwrap_or_default();
+                                let fix_path = check_path.clone();
+                                rsx! {
+                                    div { class: "check-item",
+                                        span { class: "flex-1 text-[13px] text-bkl-text-muted break-all", "{issue.message}" }
+                                        if let Some(fix) = fix {
+                                            button {
+                                                class: "btn-chip",
+                                                onclick: move |_| {
+                                                    actions.send(AppAction::FixProject(fix_path.clone(), fix.clone()));
+                                                },
+                                                "{fix_label}"
+                                            }
+                                        }
+                                    }
                                 }
-                            },
-                            "Copy"
+                            }
+                        }
+                        div { class: "flex gap-2 mt-5",
+                            button {
+                                class: "btn-chip",
+                                onclick: move |_| project_check.set(None),
+                                "Close"
+                            }
                         }
                     }
-                    div { class: "log-viewer",
-                        for (idx , line) in logs.read().iter().enumerate() {
-                            div {
-                                key: "{idx}",
-                                class: "log-line {logger::log_level_class(line)}",
-                                span { class: "text-bkl-text-faint shrink-0 select-all",
-                                    "{logger::extract_timestamp(line)}"
+                }
+            }
+
+            // ---- Cargo results ----
+            if let Some((cargo_path, task, outcome)) = cargo_result.read().clone() {
+                {
+                    let error_count = outcome.count("error");
+                    let warning_count = outcome.count("warning");
+                    rsx! {
+                        div { class: "modal-overlay",
+                            div { class: "modal max-w-[640px]",
+                                h3 { class: "m-0 mb-1 text-base font-bold text-bkl-text",
+                                    "cargo {task.command()}"
+                                }
+                                p { class: "m-0 mb-3 text-[11px] text-bkl-text-faint break-all", "{cargo_path}" }
+                                p {
+                                    class: if outcome.success { "m-0 mb-3 text-[13px] text-bkl-green" } else { "m-0 mb-3 text-[13px] text-bkl-red" },
+                                    if outcome.success { "Finished" } else { "Failed" }
+                                    " \u{00b7} {error_count} error(s), {warning_count} warning(s)"
+                                }
+                                div { class: "diagnostic-list",
+                                    for diagnostic in outcome.diagnostics.iter().cloned() {
+                                        {
+                                            let openable = diagnostic.path.is_some();
+                                            let location = diagnostic.location();
+                                            let level = diagnostic.level.clone();
+                                            let message = diagnostic.message.clone();
+                                            rsx! {
+                                                button {
+                                                    class: "diagnostic-item",
+                                                    disabled: !openable,
+                                                    title: if openable { "Open in the editor" } else { "Only available inside the Dev Container" },
+                                                    onclick: move |_| {
+                                                        actions.send(AppAction::OpenDiagnostic(diagnostic.clone()));
+                                                    },
+                                                    span { class: "diagnostic-level diagnostic-{level}", "{level}" }
+                                                    span { class: "flex-1 text-[12px] text-bkl-text-muted break-all", "{message}" }
+                                                    span { class: "text-[11px] font-mono text-bkl-text-faint", "{location}" }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                if let Some(elf) = outcome.elf.clone() {
+                                    div { class: "check-item",
+                                        span { class: "flex-1 text-[12px] font-mono text-bkl-text-muted break-all", "{elf}" }
+                                        button {
+                                            class: "btn-chip",
+                                            onclick: move |_| {
+                                                if let Ok(mut cb) = arboard::Clipboard::new() {
+                                                    let _ = cb.set_text(elf.clone());
+                                                }
+                                            },
+                                            "Copy path"
+                                        }
+                                    }
                                 }
-                                span { class: "log-badge {logger::log_badge_class(line)}",
-                                    "{logger::extract_level(line)}"
+                                div { class: "flex gap-2 mt-5",
+                                    button {
+                                        class: "btn-chip",
+                                        onclick: move |_| cargo_result.set(None),
+                                        "Close"
+                                    }
                                 }
-                                span { class: "break-all", "{logger::extract_message(line)}" }
                             }
                         }
                     }
                 }
             }
 
-            // ---- Error toast ----
-            if let Some(err) = last_error.read().clone() {
-                div { class: "error-toast",
-                    div { class: "flex items-start justify-between gap-3",
-                        span { "[ERROR] {err}" }
-                        button {
-                            class: "shrink-0 text-bkl-text-faint text-lg leading-none cursor-pointer bg-transparent border-none p-0 hover:text-bkl-red",
-                            onclick: move |_| last_error.set(None),
-                            "\u{00d7}"
+            // ---- Probe firmware update ----
+            if let Some(step) = firmware_step.read().clone() {
+                div { class: "modal-overlay",
+                    div { class: "modal max-w-[520px]",
+                        h3 { class: "m-0 mb-3 text-base font-bold text-bkl-text", "Update Probe Firmware" }
+                        ol { class: "firmware-steps",
+                            li { class: if matches!(step, FirmwareStep::Bootloader { .. }) { "firmware-step-active" } else { "" }, "Enter bootloader" }
+                            li { class: if matches!(step, FirmwareStep::Flash { .. }) { "firmware-step-active" } else { "" }, "Flash firmware file" }
+                            li { class: if matches!(step, FirmwareStep::Verify) { "firmware-step-active" } else { "" }, "Verify and reconnect" }
+                        }
+                        {
+                            match step.clone() {
+                                FirmwareStep::Bootloader { waiting } => rsx! {
+                                    p { class: "m-0 mb-3 text-[13px] text-bkl-text-muted",
+                                        "Unplug the Baker link board, hold its BOOTSEL button and plug it back in. It shows up as a drive named RPI-RP2. The DAP server is stopped during the update and restarted afterwards."
+                                    }
+                                    button {
+                                        class: "btn-chip",
+                                        disabled: waiting,
+                                        onclick: move |_| actions.send(AppAction::WaitForBootloader),
+                                        if waiting {
+                                            "Waiting for RPI-RP2\u{2026}"
+                                        } else {
+                                            "Continue"
+                                        }
+                                    }
+                                },
+                                FirmwareStep::Flash { drive } => rsx! {
+                                    p { class: "m-0 mb-3 text-[13px] text-bkl-text-muted",
+                                        "Bootloader drive found at {drive.display()}. Choose the probe firmware (.uf2) to flash."
+                                    }
+                                    button {
+                                        class: "btn-chip",
+                                        onclick: move |_| {
+                                            if let Some(file) = rfd::FileDialog::new()
+                                                .add_filter("UF2 firmware", &["uf2"])
+                                                .pick_file()
+                                            {
+                                                actions.send(AppAction::FlashFirmware(file, drive.clone()));
+                                            }
+                                        },
+                                        "Choose firmware\u{2026}"
+                                    }
+                                },
+                                FirmwareStep::Verify => rsx! {
+                                    p { class: "m-0 mb-3 text-[13px] text-bkl-text-muted",
+                                        "Flashing, then waiting for the probe to restart with the new firmware\u{2026} Do not unplug the board."
+                                    }
+                                },
+                                FirmwareStep::Done(message) => rsx! {
+                                    p { class: "m-0 mb-3 text-[13px] text-bkl-green", "{message}" }
+                                },
+                                FirmwareStep::Failed(message) => rsx! {
+                                    p { class: "m-0 mb-3 text-[13px] text-bkl-red break-all", "{message}" }
+                                },
+                            }
+                        }
+                        div { class: "flex gap-2 mt-5",
+                            button {
+                                class: "btn-chip",
+                                disabled: matches!(step, FirmwareStep::Verify),
+                                onclick: move |_| {
+                                    firmware_step.set(None);
+                                    // Cancelled before flashing: give the probe back to the DAP server
+                                    if *firmware_resume_dap.read() {
+                                        firmware_resume_dap.set(false);
+                                        match resume_dap_server() {
+                                            Ok(()) => dap_running.set(true),
+                                            Err(e) => {
+                                                crate::log_error(e.clone());
+                                                last_error.set(Some(e));
+                                            }
+                                        }
+                                    }
+                                },
+                                "Close"
+                            }
                         }
                     }
                 }
             }
 
-            // ---- Docker startup prompt ----
-            if docker_status.read().contains("Off") && !*docker_prompt_dismissed.read()
-                && !*show_splash.read()
-            {
+            // ---- Project settings modal ----
+            if let Some(settings_target) = project_settings_path.read().clone() {
                 div { class: "modal-overlay",
-                    div { class: "modal",
-                        h3 { class: "m-0 mb-3 text-base font-bold text-bkl-text", "Rancher Desktop" }
-                        p { class: "m-0 mb-1 text-[13px] text-bkl-text-muted",
-                            "Docker is not running."
+                    div { class: "modal max-w-[480px]",
+                        h3 { class: "m-0 mb-1 text-base font-bold text-bkl-text", "Project Settings" }
+                        p { class: "m-0 mb-3 text-[11px] text-bkl-text-faint break-all", "{settings_target}" }
+                        p { class: "m-0 mb-3 text-[13px] text-bkl-text-muted",
+                            "Applied to the DAP server and launch.json when the project is opened from History. Leave a field blank to use the global value."
                         }
-                        p { class: "m-0 mb-1 text-[13px] text-bkl-text-muted",
-                            "Start Rancher Desktop now?"
+                        div { class: "override-form",
+                            label { "DAP port" }
+                            input {
+                                class: "input",
+                                r#type: "number",
+                                placeholder: "{dap_port}",
+                                value: "{override_port}",
+                                oninput: move |ev| override_port.set(ev.value()),
+                            }
+                            label { "Probe" }
+                            input {
+                                class: "input",
+                                list: "probe-choices",
+                                placeholder: "VID:PID[:serial]",
+                                value: "{override_probe}",
+                                oninput: move |ev| override_probe.set(ev.value()),
+                            }
+                            datalist { id: "probe-choices",
+                                for choice in probe_choices.read().iter() {
+                                    option { value: "{choice}" }
+                                }
+                            }
+                            label { "Chip" }
+                            input {
+                                class: "input",
+                                placeholder: detected_chip.read().clone().unwrap_or_else(|| "e.g. RP2040".to_string()),
+                                value: "{override_chip}",
+                                oninput: move |ev| override_chip.set(ev.value()),
+                            }
+                            label { "Speed (kHz)" }
+                            input {
+                                class: "input",
+                                r#type: "number",
+                                placeholder: "probe default",
+                                value: "{override_speed}",
+                                oninput: move |ev| override_speed.set(ev.value()),
+                            }
+                            label { "Connect under reset" }
+                            input {
+                                r#type: "checkbox",
+                                checked: *override_connect_under_reset.read(),
+                                onchange: move |ev| override_connect_under_reset.set(ev.checked()),
+                            }
                         }
                         div { class: "flex gap-2 mt-5",
                             button {
                                 class: "btn-primary",
                                 onclick: move |_| {
-                                    actions.send(AppAction::StartDocker);
-                                    docker_prompt_dismissed.set(true);
+                                    let parsed = parse_override::<u16>(&override_port.read(), "DAP port")
+                                        .and_then(|dap_port| {
+                                            Ok(settings::ProjectOverrides {
+                                                dap_port,
+                                                probe: non_empty(&override_probe.read()),
+                                                chip: non_empty(&override_chip.read()),
+                                                speed_khz: parse_override(&override_speed.read(), "Speed")?,
+                                                connect_under_reset: *override_connect_under_reset.read(),
+                                            })
+                                        });
+                                    match parsed {
+                                        Ok(overrides) => {
+                                            let target = settings_target.clone();
+                                            history.set(settings::update_history(&target, |e| e.overrides = overrides));
+                                            crate::log_info(format!("Project settings saved: {}", target));
+                                            project_settings_path.set(None);
+                                        }
+                                        Err(e) => last_error.set(Some(e)),
+                                    }
                                 },
-                                "Start"
+                                "Save"
                             }
                             button {
                                 class: "btn-chip",
-                                onclick: move |_| docker_prompt_dismissed.set(true),
-                                "Not now"
+                                onclick: move |_| project_settings_path.set(None),
+                                "Cancel"
                             }
                         }
                     }
@@ -683,7 +3465,7 @@ pub fn App() -> Element {
                                 class: "btn-danger",
                                 onclick: move |_| {
                                     settings::reset_all();
-                                    history.set(vec![]);
+                                    reload_settings();
                                     show_reset_confirm.set(false);
                                     show_splash.set(true);
                                 },
@@ -699,6 +3481,41 @@ pub fn App() -> Element {
                 }
             }
 
+            // ---- Dev Container confirmation modal ----
+            if let Some((path, action)) = container_confirm.read().clone() {
+                {
+                    let confirm_path = path.clone();
+                    rsx! {
+                        div { class: "modal-overlay",
+                            div { class: "modal",
+                                h3 { class: "m-0 mb-3 text-base font-bold text-bkl-text",
+                                    "{action.label()} Dev Container"
+                                }
+                                p { class: "m-0 mb-1 text-[13px] text-bkl-text-muted break-all", "{path}" }
+                                p { class: "m-0 mb-1 text-[13px] text-bkl-text-muted",
+                                    "{action.warning().unwrap_or_default()}"
+                                }
+                                div { class: "flex gap-2 mt-5",
+                                    button {
+                                        class: "btn-danger",
+                                        onclick: move |_| {
+                                            container_confirm.set(None);
+                                            actions.send(AppAction::DevContainer(confirm_path.clone(), action));
+                                        },
+                                        "{action.label()}"
+                                    }
+                                    button {
+                                        class: "btn-chip",
+                                        onclick: move |_| container_confirm.set(None),
+                                        "Cancel"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
             // ---- Splash screen ----
             if *show_splash.read() {
                 div {
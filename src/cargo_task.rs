@@ -0,0 +1,218 @@
+use std::path::Path;
+
+use serde_json::Value;
+
+/// Cargo commands run inside a project's Dev Container from History.
+#[derive(Clone, Copy, PartialEq)]
+pub enum CargoTask {
+    Build,
+    Test,
+    Clippy,
+}
+
+impl CargoTask {
+    pub const ALL: [CargoTask; 3] = [CargoTask::Build, CargoTask::Test, CargoTask::Clippy];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CargoTask::Build => "Build",
+            CargoTask::Test => "Test",
+            CargoTask::Clippy => "Clippy",
+        }
+    }
+
+    /// Cargo subcommand, for log messages and titles.
+    pub fn command(self) -> &'static str {
+        self.args()[0]
+    }
+
+    /// Arguments after `cargo`. JSON messages carry the diagnostics; the
+    /// human-readable progress still goes to stderr.
+    pub fn args(self) -> &'static [&'static str] {
+        match self {
+            CargoTask::Build => &["build", "--message-format=json"],
+            CargoTask::Test => &["test", "--message-format=json"],
+            CargoTask::Clippy => &["clippy", "--message-format=json"],
+        }
+    }
+}
+
+/// A compiler message with its primary location, relative to the workspace.
+#[derive(Clone, PartialEq)]
+pub struct Diagnostic {
+    /// `error`, `warning`, `note`, ...
+    pub level: String,
+    pub message: String,
+    pub file: Option<String>,
+    /// Host path of `file` to open in the editor. `None` for dependency and
+    /// toolchain sources, which only exist inside the container.
+    pub path: Option<String>,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl Diagnostic {
+    /// `src/main.rs:12:5`, or an empty string without a location.
+    pub fn location(&self) -> String {
+        match &self.file {
+            Some(file) => format!("{}:{}:{}", file, self.line, self.column),
+            None => String::new(),
+        }
+    }
+}
+
+/// Result of a [`CargoTask`], shown in the Cargo results panel.
+#[derive(Clone, Default, PartialEq)]
+pub struct CargoOutcome {
+    /// Exit status of cargo, so failing tests count as a failure.
+    pub success: bool,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Host path of the linked binary, ready for flashing.
+    pub elf: Option<String>,
+}
+
+impl CargoOutcome {
+    pub fn count(&self, level: &str) -> usize {
+        self.diagnostics.iter().filter(|d| d.level == level).count()
+    }
+}
+
+/// Folds one stdout line into `outcome` and returns the text to log. Lines
+/// that are not cargo JSON (test output) are logged as they are.
+pub fn parse_line(
+    line: &str,
+    workspace: &str,
+    project: &Path,
+    outcome: &mut CargoOutcome,
+) -> Vec<String> {
+    let Ok(message) = serde_json::from_str::<Value>(line) else {
+        return vec![line.to_string()];
+    };
+    match message["reason"].as_str() {
+        Some("compiler-message") => {
+            let msg = &message["message"];
+            let level = msg["level"].as_str().unwrap_or_default().to_string();
+            // Summary lines such as "aborting due to 2 previous errors" have no span
+            let span = msg["spans"]
+                .as_array()
+                .and_then(|spans| spans.iter().find(|s| s["is_primary"] == true));
+            if let Some(span) = span {
+                let file = span["file_name"].as_str();
+                outcome.diagnostics.push(Diagnostic {
+                    level,
+                    message: msg["message"].as_str().unwrap_or_default().to_string(),
+                    file: file.map(str::to_string),
+                    path: file.and_then(|file| workspace_file(file, workspace, project)),
+                    line: span["line_start"].as_u64().unwrap_or(1) as u32,
+                    column: span["column_start"].as_u64().unwrap_or(1) as u32,
+                });
+            }
+            msg["rendered"]
+                .as_str()
+                .unwrap_or_default()
+                .lines()
+                .map(str::to_string)
+                .collect()
+        }
+        Some("compiler-artifact") => {
+            let is_bin = message["target"]["kind"]
+                .as_array()
+                .is_some_and(|kinds| kinds.iter().any(|k| k == "bin"));
+            let is_test = message["profile"]["test"] == true;
+            if let (true, false, Some(executable)) =
+                (is_bin, is_test, message["executable"].as_str())
+            {
+                outcome.elf = Some(host_path(executable, workspace, project));
+            }
+            Vec::new()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Host path of a compiler span file: relative ones are in the workspace,
+/// absolute ones only when they lie under it.
+fn workspace_file(file: &str, workspace: &str, project: &Path) -> Option<String> {
+    if !file.starts_with('/') {
+        return Some(project.join(file).to_string_lossy().to_string());
+    }
+    let rest = file.strip_prefix(workspace.trim_end_matches('/'))?;
+    rest.starts_with('/')
+        .then(|| host_path(file, workspace, project))
+}
+
+/// Maps a path inside the container's workspace back to the host project.
+fn host_path(container_path: &str, workspace: &str, project: &Path) -> String {
+    match container_path.strip_prefix(workspace) {
+        Some(rest) => project
+            .join(rest.trim_start_matches('/'))
+            .to_string_lossy()
+            .to_string(),
+        None => container_path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKSPACE: &str = "/workspaces/blinky";
+
+    fn parse(line: &str, outcome: &mut CargoOutcome) -> Vec<String> {
+        parse_line(line, WORKSPACE, Path::new("/home/me/blinky"), outcome)
+    }
+
+    #[test]
+    fn compiler_message_becomes_diagnostic() {
+        let line = r#"{"reason":"compiler-message","message":{"level":"warning","message":"unused variable: `x`","rendered":"warning: unused variable\n --> src/main.rs:3:9\n","spans":[{"file_name":"src/main.rs","line_start":3,"column_start":9,"is_primary":true}]}}"#;
+        let mut outcome = CargoOutcome::default();
+        let logged = parse(line, &mut outcome);
+        assert_eq!(logged, ["warning: unused variable", " --> src/main.rs:3:9"]);
+        let diagnostic = &outcome.diagnostics[0];
+        assert_eq!(diagnostic.level, "warning");
+        assert_eq!(diagnostic.location(), "src/main.rs:3:9");
+        let expected = Path::new("/home/me/blinky").join("src/main.rs");
+        assert_eq!(
+            diagnostic.path.as_deref(),
+            Some(expected.to_string_lossy().as_ref())
+        );
+    }
+
+    #[test]
+    fn dependency_sources_cannot_be_opened() {
+        let line = r#"{"reason":"compiler-message","message":{"level":"error","message":"e","rendered":"","spans":[{"file_name":"/usr/local/cargo/registry/src/x/lib.rs","line_start":1,"column_start":1,"is_primary":true}]}}"#;
+        let mut outcome = CargoOutcome::default();
+        parse(line, &mut outcome);
+        assert_eq!(outcome.diagnostics[0].path, None);
+        assert_eq!(
+            workspace_file(
+                "/workspaces/blinky-2/src/main.rs",
+                WORKSPACE,
+                Path::new("/p")
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn binary_artifact_maps_to_host() {
+        let line = r#"{"reason":"compiler-artifact","target":{"kind":["bin"]},"profile":{"test":false},"executable":"/workspaces/blinky/target/thumbv6m-none-eabi/debug/blinky"}"#;
+        let mut outcome = CargoOutcome::default();
+        assert!(parse(line, &mut outcome).is_empty());
+        let expected = Path::new("/home/me/blinky").join("target/thumbv6m-none-eabi/debug/blinky");
+        assert_eq!(
+            outcome.elf.as_deref(),
+            Some(expected.to_string_lossy().as_ref())
+        );
+    }
+
+    #[test]
+    fn other_lines_are_logged_as_is() {
+        let mut outcome = CargoOutcome::default();
+        assert_eq!(
+            parse("test it_works ... ok", &mut outcome),
+            ["test it_works ... ok"]
+        );
+        assert!(outcome == CargoOutcome::default());
+    }
+}
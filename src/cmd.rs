@@ -1,9 +1,11 @@
+use std::io::{BufRead, BufReader};
 use std::net::IpAddr;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
-use std::process::Command;
+use std::process::{Command, Stdio};
 use std::thread;
 
+use anyhow::Context;
 use probe_rs::config::TargetSelector;
 use probe_rs::probe::list::Lister;
 use probe_rs::Permissions;
@@ -12,7 +14,10 @@ use time::UtcOffset;
 use tokio::runtime::Builder;
 use tokio_util::sync::CancellationToken;
 
-use crate::parameter;
+use crate::cargo_task::{self, CargoOutcome, CargoTask};
+use crate::editor::{self, Editor};
+use crate::templates::{TemplateEntry, TemplateRef, TemplateSource};
+use crate::{parameter, project, template_cache, udev};
 
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
@@ -47,8 +52,8 @@ impl Default for DapServerStatus {
 impl Default for ProbeRsDapServer {
     fn default() -> Self {
         Self {
-            port: 50001.to_string(),
-            ip: "127.0.0.1".to_string(),
+            port: parameter::DEFAULT_DAP_PORT.to_string(),
+            ip: parameter::DEFAULT_DAP_IP.to_string(),
             shutdown: None,
             handle: None,
             status: DapServerStatus::Stopped,
@@ -56,69 +61,604 @@ impl Default for ProbeRsDapServer {
     }
 }
 
-pub fn open_vscode(path: &str) -> Result<std::process::Output, std::io::Error> {
+/// Looks up an executable on `PATH`, trying the Windows launcher extensions.
+pub fn find_in_path(name: &str) -> Option<std::path::PathBuf> {
+    find_in_dirs(&std::env::var_os("PATH")?, name)
+}
+
+fn find_in_dirs(path: &std::ffi::OsStr, name: &str) -> Option<std::path::PathBuf> {
+    let extensions: &[&str] = if cfg!(target_os = "windows") {
+        &[".cmd", ".exe", ".bat", ""]
+    } else {
+        &[""]
+    };
+    std::env::split_paths(path).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{name}{ext}")))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// PATH with the folders container engine installers use, which a GUI app
+/// started from the desktop often does not inherit.
+fn engine_search_path() -> String {
+    let current_path = std::env::var("PATH").unwrap_or_default();
+    #[cfg(target_os = "windows")]
+    {
+        let program_files = std::env::var("ProgramFiles").unwrap_or_default();
+        format!("{};{}\\RedHat\\Podman", current_path, program_files)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let home_dir = std::env::var("HOME").unwrap_or_default();
+        format!(
+            "{}/.rd/bin:/usr/local/bin:/opt/homebrew/bin:/opt/podman/bin:/usr/bin:/bin:{}",
+            home_dir, current_path
+        )
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let home_dir = std::env::var("HOME").unwrap_or_default();
+        format!(
+            "{}/.rd/bin:/usr/local/bin:/usr/bin:/usr/sbin:/bin:{}",
+            home_dir, current_path
+        )
+    }
+}
+
+/// Looks up a container engine CLI (`rdctl`, `podman`, `colima`, ...).
+pub fn find_engine_cli(name: &str) -> Option<std::path::PathBuf> {
+    find_in_dirs(std::ffi::OsStr::new(&engine_search_path()), name)
+}
+
+fn engine_command(program: &str) -> Command {
+    let mut command = Command::new(program);
+    command.env("PATH", engine_search_path());
+    #[cfg(target_os = "windows")]
+    {
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command
+}
+
+/// Whether `program args` succeeds and prints something, e.g. `docker info`.
+pub fn engine_probe(program: &str, args: &[&str]) -> Result<bool, String> {
+    let output = engine_command(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("{} {} failed: {}", program, args.join(" "), e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(output.status.success() && !stdout.trim().is_empty())
+}
+
+/// Starts a long-running engine command without waiting for it.
+pub fn engine_spawn(program: &str, args: &[&str]) -> Result<(), String> {
+    engine_command(program)
+        .args(args)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Error: {}", e))
+}
+
+/// Runs an engine command to completion, returning its stderr on failure.
+pub fn engine_run(program: &str, args: &[&str]) -> Result<(), String> {
+    let output = engine_command(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("{} {} failed: {}", program, args.join(" "), e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Creates or starts the project's Dev Container with the devcontainer CLI
+/// (`@devcontainers/cli`). `rebuild` recreates it without the build cache.
+pub fn dev_container_up(cli: &std::path::Path, project: &str, rebuild: bool) -> Result<(), String> {
+    let mut args = vec!["up", "--workspace-folder", project];
+    if rebuild {
+        args.extend(["--remove-existing-container", "--build-no-cache"]);
+    }
+    engine_run(&cli.to_string_lossy(), &args)
+}
+
+/// Builds the project's Dev Container image with the devcontainer CLI so the
+/// first "Reopen in Container" finds it in the cache.
+pub fn dev_container_build(cli: &std::path::Path, project: &str) -> Result<(), String> {
+    engine_run(
+        &cli.to_string_lossy(),
+        &["build", "--workspace-folder", project],
+    )
+}
+
+/// Runs `cargo` for `task` in the project's Dev Container and logs stderr and
+/// the rendered diagnostics line by line. Uses the devcontainer CLI when
+/// installed and `docker exec` on `container_id` otherwise.
+pub fn run_cargo_in_container(
+    project: &std::path::Path,
+    container_id: &str,
+    task: CargoTask,
+) -> Result<CargoOutcome, String> {
+    let (workspace, user) = project::container_exec_target(project);
+    let mut command = match find_engine_cli("devcontainer") {
+        Some(cli) => {
+            let mut command = engine_command(&cli.to_string_lossy());
+            command
+                .args(["exec", "--workspace-folder"])
+                .arg(project)
+                .arg("cargo");
+            command
+        }
+        None => {
+            let mut command = engine_command("docker");
+            command.args(["exec", "-w", &workspace]);
+            if let Some(user) = &user {
+                command.args(["-u", user]);
+            }
+            command.args([container_id, "cargo"]);
+            command
+        }
+    };
+    let mut child = command
+        .args(task.args())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("cargo {} failed to start: {}", task.label(), e))?;
+
+    // cargo writes progress to stderr and JSON messages to stdout
+    let stderr = child.stderr.take();
+    let stderr_thread = thread::spawn(move || {
+        if let Some(stderr) = stderr {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                crate::log_info(line);
+            }
+        }
+    });
+    let mut outcome = CargoOutcome::default();
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            for text in cargo_task::parse_line(&line, &workspace, project, &mut outcome) {
+                crate::log_info(text);
+            }
+        }
+    }
+    let _ = stderr_thread.join();
+    let status = child
+        .wait()
+        .map_err(|e| format!("cargo {} failed: {}", task.label(), e))?;
+    outcome.success = status.success();
+    Ok(outcome)
+}
+
+/// Opens `path` in `editor`. `custom_command` is only used for [`Editor::Custom`].
+pub fn open_in_editor(editor: Editor, custom_command: &str, path: &str) -> Result<(), String> {
+    if editor == Editor::Custom {
+        let args = editor::expand_command(custom_command, path)?;
+        return run_custom_editor(&args);
+    }
     #[cfg(target_os = "windows")]
     {
-        open_vscode_windows(path)
+        open_editor_windows(editor, path)
     }
     #[cfg(target_os = "macos")]
     {
-        open_vscode_macos(path)
+        open_editor_macos(editor, path)
     }
     #[cfg(target_os = "linux")]
     {
-        open_vscode_linux(path)
+        open_editor_linux(editor, path)
     }
 }
 
-pub fn start_rd() -> std::result::Result<(), String> {
+/// Opens `file` at `line:column`. Editors without a goto option open the file.
+pub fn open_file_at(
+    editor: Editor,
+    custom_command: &str,
+    file: &str,
+    line: u32,
+    column: u32,
+) -> Result<(), String> {
+    let location = format!("{}:{}:{}", file, line, column);
+    let spawned = match (editor, editor.find_cli()) {
+        (Editor::Zed, Some(cli)) => editor_cli_command(&cli).arg(&location).spawn(),
+        (_, Some(cli)) if editor.supports_extensions() => {
+            editor_cli_command(&cli).args(["--goto", &location]).spawn()
+        }
+        _ => return open_in_editor(editor, custom_command, file),
+    };
+    spawned.map(|_| ()).map_err(|e| format!("Error: {}", e))
+}
+
+/// Opens a Dev Container folder URI from [`project::dev_container_uri`] with
+/// `--folder-uri`. Only for editors where `supports_dev_container` is true.
+pub fn open_in_dev_container(editor: Editor, uri: &str) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
-        start_rd_windows()
+        open_dev_container_windows(editor, uri)
     }
     #[cfg(target_os = "macos")]
     {
-        start_rd_macos()
+        open_dev_container_macos(editor, uri)
     }
     #[cfg(target_os = "linux")]
     {
-        start_rd_linux()
+        open_dev_container_linux(editor, uri)
+    }
+}
+
+/// Extension ids from `<cli> --list-extensions`.
+pub fn list_editor_extensions(cli: &std::path::Path) -> Result<Vec<String>, String> {
+    let output = editor_cli_command(cli)
+        .arg("--list-extensions")
+        .output()
+        .map_err(|e| format!("{}: {e}", cli.display()))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} --list-extensions failed: {}",
+            cli.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+pub fn install_editor_extension(cli: &std::path::Path, id: &str) -> Result<(), String> {
+    let output = editor_cli_command(cli)
+        .args(["--install-extension", id])
+        .output()
+        .map_err(|e| format!("{}: {e}", cli.display()))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Installing {id} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn editor_cli_command(cli: &std::path::Path) -> Command {
+    let mut command = Command::new(cli);
+    #[cfg(target_os = "windows")]
+    {
+        command
+            .env("PATH", std::env::var("PATH").unwrap_or_default())
+            .creation_flags(CREATE_NO_WINDOW);
     }
+    command
 }
 
+fn run_custom_editor(args: &[String]) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        run_custom_editor_windows(args)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        run_custom_editor_macos(args)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        run_custom_editor_linux(args)
+    }
+}
+
+fn editor_not_found(editor: Editor) -> String {
+    format!(
+        "{} was not found. Install its command-line launcher ({}) or choose another editor in Settings.",
+        editor.label(),
+        editor.clis().join(" / ")
+    )
+}
+
+/// Everything needed to generate a project, collected on the UI thread.
+pub struct GenerateRequest {
+    pub name: String,
+    pub destination: std::path::PathBuf,
+    pub template: TemplateEntry,
+    pub template_ref: Option<TemplateRef>,
+    pub defines: Vec<String>,
+    pub offline: bool,
+    pub launch: project::LaunchParams,
+}
+
+impl GenerateRequest {
+    pub fn project_dir(&self) -> std::path::PathBuf {
+        self.destination.join(&self.name)
+    }
+}
+
+/// Clones (or takes from the cache) the template, renders it into
+/// `request.destination` and writes `launch.json`. Progress goes to the log.
 pub fn generate_project(
-    name: &str,
-    path: &str,
-    branch: Option<String>,
-    tag: Option<String>,
+    request: &GenerateRequest,
+    cancel: &CancellationToken,
+) -> anyhow::Result<std::path::PathBuf> {
+    let template = &request.template;
+    let clone_dir = std::env::temp_dir().join(format!(
+        "baker-link-env-generate-{}-{}",
+        std::process::id(),
+        chrono::Local::now().timestamp_millis()
+    ));
+    let result = resolve_template(request, &clone_dir, cancel).and_then(|template_path| {
+        if cancel.is_cancelled() {
+            anyhow::bail!("Project generation cancelled");
+        }
+        crate::log_info(format!("Rendering {} into {}", template.name, request.name));
+        render_template(request, template_path)
+    });
+    let _ = std::fs::remove_dir_all(&clone_dir);
+    let project_dir = result?;
+
+    // cargo-generate cannot be interrupted, so undo its work if the user gave up meanwhile
+    if cancel.is_cancelled() {
+        let _ = std::fs::remove_dir_all(&project_dir);
+        anyhow::bail!("Project generation cancelled");
+    }
+    crate::log_info("Writing .vscode/launch.json");
+    // The project itself is usable without it, so do not fail the whole generation
+    if let Err(e) = project::write_launch_json(&project_dir, &request.launch) {
+        crate::log_error(format!(
+            "Project generated, but launch.json could not be written: {:#}",
+            e
+        ));
+    }
+    Ok(project_dir)
+}
+
+/// Where cargo-generate renders from: the template folder itself, a fresh
+/// clone in `clone_dir`, or the cached copy when offline or the clone fails.
+/// Without a `git` command, cargo-generate clones the template on its own.
+fn resolve_template(
+    request: &GenerateRequest,
+    clone_dir: &std::path::Path,
+    cancel: &CancellationToken,
+) -> anyhow::Result<cargo_generate::TemplatePath> {
+    let template = &request.template;
+    let url = match &template.source {
+        TemplateSource::Path(path) => return Ok(local_template(std::path::Path::new(path))),
+        TemplateSource::Git(url) => url,
+    };
+    let selected_ref = request
+        .template_ref
+        .as_ref()
+        .or(template.default_ref.as_ref());
+    let git_ref = selected_ref.map(TemplateRef::name);
+    let cached = template_cache::find_cached(template);
+    if request.offline {
+        let (entry, dir) =
+            cached.with_context(|| format!("{} is not cached for offline use", template.name))?;
+        match entry.ref_mismatch(git_ref) {
+            Some(warning) => crate::log_error(warning),
+            None => crate::log_info(format!(
+                "Using cached template {} {}",
+                entry.name,
+                entry.revision_label()
+            )),
+        }
+        return Ok(local_template(&dir));
+    }
+
+    if find_in_path("git").is_none() {
+        crate::log_info(format!(
+            "Git was not found; cloning {} with cargo-generate",
+            url
+        ));
+        return Ok(cargo_generate::TemplatePath {
+            git: Some(url.clone()),
+            branch: match selected_ref {
+                Some(TemplateRef::Branch(name)) => Some(name.clone()),
+                _ => None,
+            },
+            tag: match selected_ref {
+                Some(TemplateRef::Tag(name)) => Some(name.clone()),
+                _ => None,
+            },
+            ..cargo_generate::TemplatePath::default()
+        });
+    }
+
+    crate::log_info(format!(
+        "Cloning {}{}",
+        url,
+        git_ref.map(|r| format!(" ({r})")).unwrap_or_default()
+    ));
+    match clone_template(url, git_ref, clone_dir, cancel) {
+        Ok(()) => Ok(local_template(clone_dir)),
+        Err(_) if cancel.is_cancelled() => anyhow::bail!("Project generation cancelled"),
+        Err(e) => {
+            let Some((entry, dir)) = cached else {
+                anyhow::bail!(e);
+            };
+            crate::log_error(format!(
+                "{e}; using cached template {}",
+                entry.revision_label()
+            ));
+            if let Some(warning) = entry.ref_mismatch(git_ref) {
+                crate::log_error(warning);
+            }
+            Ok(local_template(&dir))
+        }
+    }
+}
+
+fn local_template(dir: &std::path::Path) -> cargo_generate::TemplatePath {
+    cargo_generate::TemplatePath {
+        path: Some(dir.to_string_lossy().to_string()),
+        ..cargo_generate::TemplatePath::default()
+    }
+}
+
+fn render_template(
+    request: &GenerateRequest,
+    template_path: cargo_generate::TemplatePath,
 ) -> anyhow::Result<std::path::PathBuf> {
-    std::env::set_current_dir(path).unwrap();
     let generate_args = cargo_generate::GenerateArgs {
-        name: Some(name.to_string()),
+        name: Some(request.name.clone()),
+        // Keep the folder named as typed, so it matches `project_dir`
+        force: true,
         vcs: Some(cargo_generate::Vcs::Git),
-        template_path: cargo_generate::TemplatePath {
-            git: Some(parameter::TUTORIAL_TEMPLATE.to_string()),
-            branch,
-            tag,
-            ..cargo_generate::TemplatePath::default()
-        },
+        template_path,
+        destination: Some(request.destination.clone()),
+        define: request.defines.clone(),
+        silent: true,
         ..cargo_generate::GenerateArgs::default()
     };
     cargo_generate::generate(generate_args)
 }
 
-pub fn is_docker_running() -> Result<bool, String> {
+/// Fails with an install hint when there is no `git` command on `PATH`.
+pub fn require_git() -> Result<(), String> {
+    find_in_path("git").map(|_| ()).ok_or_else(|| {
+        "Git was not found. Install it from https://git-scm.com/ and restart the app".to_string()
+    })
+}
+
+/// Shallow-clones a Git template; `git_ref` may be a branch or a tag.
+/// The clone is killed as soon as `cancel` fires.
+pub fn clone_template(
+    url: &str,
+    git_ref: Option<&str>,
+    dest: &std::path::Path,
+    cancel: &CancellationToken,
+) -> Result<(), String> {
+    require_git()?;
+    let mut command = Command::new("git");
+    command.arg("clone").arg("--depth").arg("1");
+    if let Some(git_ref) = git_ref {
+        command.arg("--branch").arg(git_ref);
+    }
+    command
+        .arg(url)
+        .arg(dest)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped());
     #[cfg(target_os = "windows")]
-    {
-        docker_info_windows()
+    command.creation_flags(CREATE_NO_WINDOW);
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("git clone failed: {}", e))?;
+    loop {
+        if cancel.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("git clone cancelled".to_string());
+        }
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) => thread::sleep(std::time::Duration::from_millis(100)),
+            Err(e) => return Err(format!("git clone failed: {}", e)),
+        }
     }
-    #[cfg(target_os = "macos")]
-    {
-        docker_info_macos()
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("git clone failed: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "git clone failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
     }
-    #[cfg(target_os = "linux")]
-    {
-        docker_info_linux()
+}
+
+/// How long [`git_ls_remote`] waits before calling the remote unreachable.
+const LS_REMOTE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+
+/// Whether a Git template URL answers, without cloning it.
+pub fn git_ls_remote(url: &str) -> Result<(), String> {
+    require_git()?;
+    let mut command = Command::new("git");
+    command
+        .args(["ls-remote", "--heads", url])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    #[cfg(target_os = "windows")]
+    command.creation_flags(CREATE_NO_WINDOW);
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("git ls-remote failed: {}", e))?;
+    // A silently dropped connection would otherwise hang until the OS gives up
+    let started = std::time::Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() >= LS_REMOTE_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "git ls-remote timed out after {} s",
+                    LS_REMOTE_TIMEOUT.as_secs()
+                ));
+            }
+            Ok(None) => thread::sleep(std::time::Duration::from_millis(100)),
+            Err(e) => return Err(format!("git ls-remote failed: {}", e)),
+        }
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("git ls-remote failed: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "git ls-remote failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Installs the generated udev rules with pkexec, which shows the desktop's
+/// password prompt (Linux only).
+pub fn install_udev_rules(source: &std::path::Path) -> Result<(), String> {
+    if !cfg!(target_os = "linux") {
+        return Err("udev rules are only used on Linux".to_string());
+    }
+    let output = Command::new("pkexec")
+        .args(["sh", "-c", &udev::install_script(), "sh"])
+        .arg(source)
+        .output()
+        .map_err(|e| format!("pkexec failed: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Installing udev rules failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Commit hash checked out in a cloned repository.
+pub fn git_head_commit(repo: &std::path::Path) -> Result<String, String> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(repo).arg("rev-parse").arg("HEAD");
+    #[cfg(target_os = "windows")]
+    command.creation_flags(CREATE_NO_WINDOW);
+    let output = command
+        .output()
+        .map_err(|e| format!("git rev-parse failed: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!(
+            "git rev-parse failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
     }
 }
 
@@ -158,6 +698,13 @@ impl ProbeRsDapServer {
         true
     }
 
+    pub fn running_port(&self) -> Option<u16> {
+        match self.status {
+            DapServerStatus::Running(port) => Some(port),
+            DapServerStatus::Stopped => None,
+        }
+    }
+
     fn parse_port(&self) -> Result<u16, String> {
         self.port
             .parse::<u16>()
@@ -206,90 +753,122 @@ fn spawn_dap_server_thread(
 }
 
 #[cfg(target_os = "windows")]
-fn open_vscode_windows(path: &str) -> Result<std::process::Output, std::io::Error> {
-    let env_path = std::env::var("PATH").unwrap();
-    std::process::Command::new("code.cmd")
+fn open_editor_windows(editor: Editor, path: &str) -> Result<(), String> {
+    let env_path = std::env::var("PATH").unwrap_or_default();
+    if editor == Editor::Neovim {
+        // Windows Terminal when available, otherwise a new console window
+        let mut command = match find_in_path("wt") {
+            Some(wt) => {
+                let mut command = Command::new(wt);
+                command.args(["-d", path, "nvim", "."]);
+                command
+            }
+            None => {
+                let mut command = Command::new("cmd");
+                command.args(["/C", "start", "", "/D", path, "nvim", "."]);
+                command
+            }
+        };
+        return command
+            .env("PATH", env_path)
+            .creation_flags(CREATE_NO_WINDOW)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Neovim: {e}"));
+    }
+    let cli = editor.find_cli().ok_or_else(|| editor_not_found(editor))?;
+    Command::new(cli)
         .arg(path)
         .env("PATH", env_path)
         .creation_flags(CREATE_NO_WINDOW)
-        .output()
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("{}: {e}", editor.label()))
 }
 
-#[cfg(target_os = "macos")]
-fn open_vscode_macos(path: &str) -> Result<std::process::Output, std::io::Error> {
-    std::process::Command::new("open")
-        .arg("-a")
-        .arg("Visual Studio Code")
-        .arg(path)
-        .output()
+#[cfg(target_os = "windows")]
+fn open_dev_container_windows(editor: Editor, uri: &str) -> Result<(), String> {
+    let cli = editor.find_cli().ok_or_else(|| editor_not_found(editor))?;
+    Command::new(cli)
+        .args(["--folder-uri", uri])
+        .env("PATH", std::env::var("PATH").unwrap_or_default())
+        .creation_flags(CREATE_NO_WINDOW)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("{}: {e}", editor.label()))
 }
 
 #[cfg(target_os = "windows")]
-fn start_rd_windows() -> std::result::Result<(), String> {
-    let path = std::env::var("PATH").unwrap_or_default();
-    match std::process::Command::new("rdctl")
-        .arg("start")
-        .arg("--application.start-in-background")
-        .env("PATH", &path)
+fn run_custom_editor_windows(args: &[String]) -> Result<(), String> {
+    Command::new(&args[0])
+        .args(&args[1..])
+        .env("PATH", std::env::var("PATH").unwrap_or_default())
         .creation_flags(CREATE_NO_WINDOW)
         .spawn()
-    {
-        Ok(_) => Ok(()),
-        Err(e) => Err(format!("Error: {}", e)),
-    }
+        .map(|_| ())
+        .map_err(|e| format!("{}: {e}", args[0]))
 }
 
 #[cfg(target_os = "macos")]
-fn start_rd_macos() -> std::result::Result<(), String> {
-    let home_dir = std::env::var("HOME").unwrap();
-    let zshrc_path = format!("{}/{}", home_dir, ZSH_PROFILE);
-
-    match std::process::Command::new("zsh")
-        .arg("-c")
-        .arg(format!(
-            "source {} && rdctl start --application.start-in-background",
-            zshrc_path
+fn open_editor_macos(editor: Editor, path: &str) -> Result<(), String> {
+    let output = match editor.mac_app() {
+        Some(app) => Command::new("open").arg("-a").arg(app).arg(path).output(),
+        None => {
+            // Neovim: run it in a new Terminal.app window
+            let script = format!(
+                "tell application \"Terminal\"\nactivate\ndo script \"cd {} && nvim .\"\nend tell",
+                shell_quote(path).replace('\\', "\\\\").replace('"', "\\\"")
+            );
+            Command::new("osascript").arg("-e").arg(script).output()
+        }
+    }
+    .map_err(|e| format!("{}: {e}", editor.label()))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{}: {}",
+            editor_not_found(editor),
+            String::from_utf8_lossy(&output.stderr).trim()
         ))
-        .spawn()
-    {
-        Ok(_) => Ok(()),
-        Err(e) => Err(format!("Error: {}", e)),
     }
 }
 
-#[cfg(target_os = "windows")]
-fn docker_info_windows() -> Result<bool, String> {
-    let path = std::env::var("PATH").unwrap_or_default();
-    let output = Command::new("docker")
-        .arg("info")
-        .arg("--format")
-        .arg("{{.ServerVersion}}")
-        .env("PATH", path)
-        .creation_flags(CREATE_NO_WINDOW)
-        .output()
-        .map_err(|e| format!("docker info failed: {}", e))?;
-    Ok(output.status.success())
+#[cfg(target_os = "macos")]
+fn open_dev_container_macos(editor: Editor, uri: &str) -> Result<(), String> {
+    // `open -a` cannot pass --folder-uri, so use the CLI from PATH or the app bundle
+    let cli = editor.find_cli().ok_or_else(|| editor_not_found(editor))?;
+    Command::new(cli)
+        .args(["--folder-uri", uri])
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("{}: {e}", editor.label()))
 }
 
 #[cfg(target_os = "macos")]
-fn docker_info_macos() -> Result<bool, String> {
+fn run_custom_editor_macos(args: &[String]) -> Result<(), String> {
+    // Through zsh so the launcher is found on the user's PATH
     let home_dir = std::env::var("HOME").unwrap_or_default();
-    let current_path = std::env::var("PATH").unwrap_or_default();
-    let full_path = format!(
-        "{}/.rd/bin:/usr/local/bin:/opt/homebrew/bin:/usr/bin:/bin:{}",
-        home_dir, current_path
-    );
-    let output = Command::new("docker")
-        .arg("info")
-        .arg("--format")
-        .arg("{{.ServerVersion}}")
-        .env("PATH", full_path)
-        .output()
-        .map_err(|e| format!("docker info failed: {}", e))?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    Ok(output.status.success() && !stdout.trim().is_empty())
+    let zshrc_path = format!("{}/{}", home_dir, ZSH_PROFILE);
+    let command_line: Vec<String> = args.iter().map(|arg| shell_quote(arg)).collect();
+    Command::new("zsh")
+        .arg("-c")
+        .arg(format!(
+            "source {} && {}",
+            zshrc_path,
+            command_line.join(" ")
+        ))
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("{}: {e}", args[0]))
+}
+
+/// Quotes `arg` as one POSIX shell word.
+pub fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
 }
 
+#[derive(Clone, PartialEq)]
 pub struct ProbeInfo {
     pub probe_type: String,
     pub identifier: String,
@@ -305,6 +884,18 @@ pub struct TargetInfo {
     pub target_voltage: Option<f32>,
 }
 
+impl ProbeInfo {
+    /// `VID:PID[:serial]`, as accepted by probe-rs' `probe` option.
+    pub fn selector(&self) -> String {
+        let mut selector = format!("{:04x}:{:04x}", self.vendor_id, self.product_id);
+        if let Some(serial) = &self.serial_number {
+            selector.push(':');
+            selector.push_str(serial);
+        }
+        selector
+    }
+}
+
 pub fn list_probes() -> Vec<ProbeInfo> {
     Lister::new()
         .list_all()
@@ -322,7 +913,7 @@ pub fn list_probes() -> Vec<ProbeInfo> {
 pub fn detect_target() -> Result<TargetInfo, String> {
     let lister = Lister::new();
     let probes = lister.list_all();
-    let probe_info_raw = probes.first().ok_or("No debug probe found".to_string())?;
+    let probe_info_raw = probes.first().ok_or_else(udev::no_probe_error)?;
 
     let probe_info = ProbeInfo {
         probe_type: probe_info_raw.probe_type(),
@@ -332,9 +923,25 @@ pub fn detect_target() -> Result<TargetInfo, String> {
         serial_number: probe_info_raw.serial_number.clone(),
     };
 
-    let probe = probe_info_raw
-        .open()
-        .map_err(|e| format!("Failed to open probe: {e}"))?;
+    let probe = probe_info_raw.open().map_err(|e| {
+        // The USB error text rarely says "permission", so also check the device node
+        let denied: Vec<_> = udev::denied_devices()
+            .into_iter()
+            .filter(|d| {
+                d.vendor_id == probe_info_raw.vendor_id && d.product_id == probe_info_raw.product_id
+            })
+            .collect();
+        if !denied.is_empty() {
+            udev::explain(&denied)
+        } else if cfg!(target_os = "linux") && udev::is_permission_error(&e.to_string()) {
+            format!(
+                "No permission to open {}. Install the udev rules from Doctor \u{2192} USB permissions and replug the board.",
+                probe_info_raw.identifier
+            )
+        } else {
+            format!("Failed to open probe: {e}")
+        }
+    })?;
 
     let session = probe
         .attach(TargetSelector::Auto, Permissions::default())
@@ -355,43 +962,55 @@ pub fn detect_target() -> Result<TargetInfo, String> {
     })
 }
 
+/// Terminal emulators tried for Neovim, with the flag that precedes the command.
 #[cfg(target_os = "linux")]
-fn linux_path_with_rd() -> String {
-    let home_dir = std::env::var("HOME").unwrap_or_default();
-    let current_path = std::env::var("PATH").unwrap_or_default();
-    format!(
-        "{}/.rd/bin:/usr/local/bin:/usr/bin:/bin:{}",
-        home_dir, current_path
-    )
-}
+const LINUX_TERMINALS: &[(&str, &str)] = &[
+    ("x-terminal-emulator", "-e"),
+    ("gnome-terminal", "--"),
+    ("konsole", "-e"),
+    ("xfce4-terminal", "-x"),
+    ("alacritty", "-e"),
+    ("kitty", "--"),
+    ("xterm", "-e"),
+];
 
 #[cfg(target_os = "linux")]
-fn open_vscode_linux(path: &str) -> Result<std::process::Output, std::io::Error> {
-    std::process::Command::new("code").arg(path).output()
+fn open_editor_linux(editor: Editor, path: &str) -> Result<(), String> {
+    if editor == Editor::Neovim {
+        let (terminal, flag) = LINUX_TERMINALS
+            .iter()
+            .find_map(|(name, flag)| find_in_path(name).map(|t| (t, *flag)))
+            .ok_or_else(|| "Neovim: no terminal emulator found".to_string())?;
+        return Command::new(terminal)
+            .args([flag, "nvim", "."])
+            .current_dir(path)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("Neovim: {e}"));
+    }
+    let cli = editor.find_cli().ok_or_else(|| editor_not_found(editor))?;
+    Command::new(cli)
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("{}: {e}", editor.label()))
 }
 
 #[cfg(target_os = "linux")]
-fn start_rd_linux() -> std::result::Result<(), String> {
-    match std::process::Command::new("rdctl")
-        .arg("start")
-        .arg("--application.start-in-background")
-        .env("PATH", linux_path_with_rd())
+fn open_dev_container_linux(editor: Editor, uri: &str) -> Result<(), String> {
+    let cli = editor.find_cli().ok_or_else(|| editor_not_found(editor))?;
+    Command::new(cli)
+        .args(["--folder-uri", uri])
         .spawn()
-    {
-        Ok(_) => Ok(()),
-        Err(e) => Err(format!("Error: {}", e)),
-    }
+        .map(|_| ())
+        .map_err(|e| format!("{}: {e}", editor.label()))
 }
 
 #[cfg(target_os = "linux")]
-fn docker_info_linux() -> Result<bool, String> {
-    let output = Command::new("docker")
-        .arg("info")
-        .arg("--format")
-        .arg("{{.ServerVersion}}")
-        .env("PATH", linux_path_with_rd())
-        .output()
-        .map_err(|e| format!("docker info failed: {}", e))?;
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    Ok(output.status.success() && !stdout.trim().is_empty())
+fn run_custom_editor_linux(args: &[String]) -> Result<(), String> {
+    Command::new(&args[0])
+        .args(&args[1..])
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("{}: {e}", args[0]))
 }
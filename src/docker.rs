@@ -0,0 +1,828 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde_json::Value;
+
+const TIMEOUT: Duration = Duration::from_secs(3);
+/// Stopping a container waits for its grace period, and removing an image can
+/// take a while on slow disks.
+const ACTION_TIMEOUT: Duration = Duration::from_secs(60);
+/// Label VS Code sets on Dev Containers, holding the host project folder.
+pub const LOCAL_FOLDER_LABEL: &str = "devcontainer.local_folder";
+/// Pulls stream progress every few seconds; this only catches a stalled one.
+const PULL_TIMEOUT: Duration = Duration::from_secs(300);
+/// `{"label":["devcontainer.local_folder"]}`, URL-encoded.
+const DEV_CONTAINER_FILTER: &str = "%7B%22label%22%3A%5B%22devcontainer.local_folder%22%5D%7D";
+
+/// Docker Engine state shown in the header. Both `version` and `error` are
+/// `None` until the first check.
+#[derive(Clone, Default, PartialEq)]
+pub struct DockerStatus {
+    /// Docker context name, or `DOCKER_HOST` when the variable is set.
+    pub context: String,
+    pub endpoint: String,
+    pub version: Option<String>,
+    pub containers_running: u64,
+    pub error: Option<String>,
+}
+
+impl DockerStatus {
+    pub fn is_up(&self) -> bool {
+        self.version.is_some()
+    }
+
+    pub fn is_down(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Tooltip with the context, endpoint and error reason.
+    pub fn details(&self) -> String {
+        let mut lines = vec![
+            format!("Context: {}", self.context),
+            format!("Endpoint: {}", self.endpoint),
+        ];
+        if let Some(error) = &self.error {
+            lines.push(error.clone());
+        }
+        lines.join("\n")
+    }
+}
+
+/// A container created by VS Code for a project's Dev Container.
+#[derive(Clone, PartialEq)]
+pub struct DevContainer {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    /// Host project folder from the `devcontainer.local_folder` label.
+    pub local_folder: String,
+    /// `running`, `exited`, `created`, `paused`, ...
+    pub state: String,
+    /// Human readable state, e.g. `Up 2 hours`.
+    pub status: String,
+    /// Creation time in Unix seconds.
+    pub created: i64,
+}
+
+impl DevContainer {
+    pub fn is_running(&self) -> bool {
+        self.state == "running"
+    }
+}
+
+/// Where the Docker Engine API listens.
+#[derive(Clone)]
+enum Endpoint {
+    Unix(PathBuf),
+    Pipe(String),
+    Tcp(String),
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Endpoint::Unix(path) => write!(f, "unix://{}", path.display()),
+            Endpoint::Pipe(pipe) => write!(f, "npipe://{}", pipe.replace('\\', "/")),
+            Endpoint::Tcp(addr) => write!(f, "tcp://{}", addr),
+        }
+    }
+}
+
+fn parse_host(host: &str) -> Result<Endpoint, String> {
+    if let Some(path) = host.strip_prefix("unix://") {
+        Ok(Endpoint::Unix(PathBuf::from(path)))
+    } else if let Some(pipe) = host.strip_prefix("npipe://") {
+        Ok(Endpoint::Pipe(pipe.replace('/', "\\")))
+    } else if let Some(addr) = host.strip_prefix("tcp://") {
+        if std::env::var("DOCKER_TLS_VERIFY").is_ok_and(|v| !v.is_empty()) {
+            return Err("TLS connections to Docker are not supported".to_string());
+        }
+        Ok(Endpoint::Tcp(addr.trim_end_matches('/').to_string()))
+    } else {
+        Err(format!("Unsupported Docker host: {}", host))
+    }
+}
+
+fn docker_config_dir() -> Option<PathBuf> {
+    std::env::var_os("DOCKER_CONFIG")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".docker")))
+}
+
+/// Current context from `DOCKER_CONTEXT` or `~/.docker/config.json`.
+fn current_context() -> Option<String> {
+    if let Ok(name) = std::env::var("DOCKER_CONTEXT") {
+        return Some(name);
+    }
+    let config = std::fs::read_to_string(docker_config_dir()?.join("config.json")).ok()?;
+    let config: Value = serde_json::from_str(&config).ok()?;
+    config["currentContext"].as_str().map(str::to_string)
+}
+
+/// Docker host stored for a named context. Context folders are named by a
+/// hash of the name, so the `meta.json` files are searched instead.
+fn context_host(name: &str) -> Option<String> {
+    let meta_dir = docker_config_dir()?.join("contexts").join("meta");
+    std::fs::read_dir(meta_dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join("meta.json")).ok())
+        .filter_map(|meta| serde_json::from_str::<Value>(&meta).ok())
+        .find(|meta| meta["Name"] == name)
+        .and_then(|meta| {
+            meta["Endpoints"]["docker"]["Host"]
+                .as_str()
+                .map(str::to_string)
+        })
+}
+
+/// Sockets the supported engines create, tried when no context is set.
+fn default_endpoints() -> Vec<Endpoint> {
+    if cfg!(target_os = "windows") {
+        return vec![
+            Endpoint::Pipe(r"\\.\pipe\docker_engine".to_string()),
+            Endpoint::Pipe(r"\\.\pipe\podman-machine-default".to_string()),
+        ];
+    }
+    let home = dirs::home_dir().unwrap_or_default();
+    let mut sockets = vec![
+        PathBuf::from("/var/run/docker.sock"),
+        home.join(".rd/docker.sock"),
+        home.join(".colima/default/docker.sock"),
+        home.join(".docker/run/docker.sock"),
+    ];
+    if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        sockets.push(PathBuf::from(runtime_dir).join("podman/podman.sock"));
+    }
+    sockets.into_iter().map(Endpoint::Unix).collect()
+}
+
+/// Resolves the endpoint like the docker CLI: `DOCKER_HOST`, then the current
+/// context, then the first default socket that exists.
+fn resolve() -> Result<(String, Endpoint), String> {
+    if let Ok(host) = std::env::var("DOCKER_HOST") {
+        if !host.is_empty() {
+            return parse_host(&host).map(|endpoint| ("DOCKER_HOST".to_string(), endpoint));
+        }
+    }
+    if let Some(name) = current_context().filter(|name| name != "default") {
+        let host =
+            context_host(&name).ok_or_else(|| format!("Docker context '{}' not found", name))?;
+        return parse_host(&host).map(|endpoint| (name, endpoint));
+    }
+    let endpoints = default_endpoints();
+    let endpoint = endpoints
+        .iter()
+        .find(|endpoint| match endpoint {
+            Endpoint::Unix(path) => path.exists(),
+            Endpoint::Pipe(pipe) => std::path::Path::new(pipe).exists(),
+            Endpoint::Tcp(_) => true,
+        })
+        .unwrap_or(&endpoints[0])
+        .clone();
+    Ok(("default".to_string(), endpoint))
+}
+
+trait Stream: Read + Write {}
+impl<T: Read + Write> Stream for T {}
+
+fn connect(endpoint: &Endpoint, timeout: Duration) -> std::io::Result<Box<dyn Stream>> {
+    match endpoint {
+        #[cfg(unix)]
+        Endpoint::Unix(path) => {
+            let stream = std::os::unix::net::UnixStream::connect(path)?;
+            stream.set_read_timeout(Some(timeout))?;
+            stream.set_write_timeout(Some(timeout))?;
+            Ok(Box::new(stream))
+        }
+        #[cfg(not(unix))]
+        Endpoint::Unix(_) => Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "Unix sockets are not available on this OS",
+        )),
+        Endpoint::Pipe(pipe) => {
+            let file = std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(pipe)?;
+            Ok(Box::new(PipeStream::new(file, timeout)))
+        }
+        Endpoint::Tcp(addr) => {
+            use std::net::ToSocketAddrs;
+            let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::NotFound, "no address for Docker host")
+            })?;
+            let stream = std::net::TcpStream::connect_timeout(&addr, TIMEOUT)?;
+            stream.set_read_timeout(Some(timeout))?;
+            stream.set_write_timeout(Some(timeout))?;
+            Ok(Box::new(stream))
+        }
+    }
+}
+
+/// A named pipe opened by `std::fs`, which has no read timeout. Once the
+/// request is written, reads move to a helper thread; when nothing arrives
+/// within `timeout` the read fails and the thread is left to end by itself
+/// when the engine answers or the pipe closes.
+struct PipeStream {
+    file: Option<std::fs::File>,
+    chunks: Option<std::sync::mpsc::Receiver<std::io::Result<Vec<u8>>>>,
+    pending: Vec<u8>,
+    timeout: Duration,
+}
+
+impl PipeStream {
+    fn new(file: std::fs::File, timeout: Duration) -> Self {
+        PipeStream {
+            file: Some(file),
+            chunks: None,
+            pending: Vec::new(),
+            timeout,
+        }
+    }
+
+    /// Synchronous pipe I/O is serialized per handle, so a read pending on
+    /// the thread would block later writes; the thread gets the file only
+    /// when reading starts.
+    fn spawn_reader(
+        file: Option<std::fs::File>,
+    ) -> std::sync::mpsc::Receiver<std::io::Result<Vec<u8>>> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        if let Some(mut file) = file {
+            std::thread::spawn(move || loop {
+                let mut chunk = vec![0; 8192];
+                match file.read(&mut chunk) {
+                    Ok(n) => {
+                        chunk.truncate(n);
+                        if tx.send(Ok(chunk)).is_err() || n == 0 {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                        break;
+                    }
+                }
+            });
+        }
+        rx
+    }
+}
+
+impl Read for PipeStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pending.is_empty() {
+            let chunks = self
+                .chunks
+                .get_or_insert_with(|| Self::spawn_reader(self.file.take()));
+            match chunks.recv_timeout(self.timeout) {
+                Ok(Ok(chunk)) => self.pending = chunk,
+                Ok(Err(e)) => return Err(e),
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        "no answer from the Docker Engine",
+                    ))
+                }
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.pending.len());
+        buf[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(..n);
+        Ok(n)
+    }
+}
+
+impl Write for PipeStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match &mut self.file {
+            Some(file) => file.write(buf),
+            None => Err(std::io::Error::other("Docker pipe is already being read")),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+fn decode_chunked(mut body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    while let Some(line_end) = body.windows(2).position(|w| w == b"\r\n") {
+        let size = std::str::from_utf8(&body[..line_end])
+            .ok()
+            .and_then(|s| usize::from_str_radix(s.split(';').next()?.trim(), 16).ok())
+            .unwrap_or(0);
+        let start = line_end + 2;
+        if size == 0 || start + size > body.len() {
+            break;
+        }
+        out.extend_from_slice(&body[start..start + size]);
+        body = &body[(start + size + 2).min(body.len())..];
+    }
+    out
+}
+
+/// Sends one request to the Engine API and returns the status code and body.
+fn request(
+    endpoint: &Endpoint,
+    method: &str,
+    path: &str,
+    timeout: Duration,
+) -> Result<(u16, String), String> {
+    let mut stream =
+        connect(endpoint, timeout).map_err(|e| format!("Cannot connect to {}: {}", endpoint, e))?;
+    let request = format!(
+        "{} {} HTTP/1.1\r\nHost: docker\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+        method, path
+    );
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| format!("Docker API request failed: {}", e))?;
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .map_err(|e| format!("Docker API response failed: {}", e))?;
+
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or("Malformed Docker API response")?;
+    let head = String::from_utf8_lossy(&response[..header_end]).to_string();
+    let code = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or("Malformed Docker API status line")?;
+    let body = &response[header_end + 4..];
+    let chunked = head
+        .lines()
+        .any(|line| line.eq_ignore_ascii_case("transfer-encoding: chunked"));
+    let body = if chunked {
+        decode_chunked(body)
+    } else {
+        body.to_vec()
+    };
+    Ok((code, String::from_utf8_lossy(&body).to_string()))
+}
+
+fn get_json(endpoint: &Endpoint, path: &str) -> Result<Value, String> {
+    let (code, body) = request(endpoint, "GET", path, TIMEOUT)?;
+    if !(200..300).contains(&code) {
+        return Err(format!(
+            "Docker API {} returned {}: {}",
+            path,
+            code,
+            body.trim()
+        ));
+    }
+    serde_json::from_str(&body).map_err(|e| format!("Docker API {}: {}", path, e))
+}
+
+/// Sends an action request and returns the response body. 304 (already
+/// started or stopped) counts as success.
+fn call(method: &str, path: &str) -> Result<String, String> {
+    let (_, endpoint) = resolve()?;
+    let (code, body) = request(&endpoint, method, path, ACTION_TIMEOUT)?;
+    if (200..300).contains(&code) || code == 304 {
+        return Ok(body);
+    }
+    let message = serde_json::from_str::<Value>(&body)
+        .ok()
+        .and_then(|v| v["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().to_string());
+    Err(format!(
+        "Docker API {} {} returned {}: {}",
+        method, path, code, message
+    ))
+}
+
+fn send(method: &str, path: &str) -> Result<(), String> {
+    call(method, path).map(|_| ())
+}
+
+/// Containers with the `devcontainer.local_folder` label, including stopped ones.
+pub fn list_dev_containers() -> Result<Vec<DevContainer>, String> {
+    let (_, endpoint) = resolve()?;
+    let containers = get_json(
+        &endpoint,
+        &format!("/containers/json?all=1&filters={}", DEV_CONTAINER_FILTER),
+    )?;
+    Ok(containers
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|c| DevContainer {
+            id: c["Id"].as_str().unwrap_or_default().to_string(),
+            name: c["Names"][0]
+                .as_str()
+                .unwrap_or_default()
+                .trim_start_matches('/')
+                .to_string(),
+            image: c["Image"].as_str().unwrap_or_default().to_string(),
+            local_folder: c["Labels"][LOCAL_FOLDER_LABEL]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            state: c["State"].as_str().unwrap_or_default().to_string(),
+            status: c["Status"].as_str().unwrap_or_default().to_string(),
+            created: c["Created"].as_i64().unwrap_or(0),
+        })
+        .collect())
+}
+
+pub fn start_container(id: &str) -> Result<(), String> {
+    send("POST", &format!("/containers/{}/start", id))
+}
+
+pub fn stop_container(id: &str) -> Result<(), String> {
+    send("POST", &format!("/containers/{}/stop", id))
+}
+
+/// Removes the container, stopping it first when it is running.
+pub fn remove_container(id: &str) -> Result<(), String> {
+    send("DELETE", &format!("/containers/{}?force=1", id))
+}
+
+pub fn remove_image(image: &str) -> Result<(), String> {
+    send("DELETE", &format!("/images/{}", image))
+}
+
+/// Space used by Docker, from `/system/df`.
+#[derive(Clone, Default, PartialEq)]
+pub struct DiskUsage {
+    pub images: Vec<ImageUsage>,
+    pub containers: usize,
+    pub containers_size: u64,
+    pub volumes: usize,
+    pub volumes_size: u64,
+    pub build_cache_size: u64,
+}
+
+impl DiskUsage {
+    pub fn images_size(&self) -> u64 {
+        self.images.iter().map(|image| image.size).sum()
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct ImageUsage {
+    pub id: String,
+    /// `repo:tag` names; empty for dangling images.
+    pub tags: Vec<String>,
+    pub size: u64,
+    /// Containers using the image, running or not.
+    pub containers: u64,
+}
+
+pub fn disk_usage() -> Result<DiskUsage, String> {
+    let body = call("GET", "/system/df")?;
+    let df: Value =
+        serde_json::from_str(&body).map_err(|e| format!("Docker API /system/df: {}", e))?;
+    let list = |key: &str| df[key].as_array().cloned().unwrap_or_default();
+    let images = list("Images")
+        .iter()
+        .map(|image| ImageUsage {
+            id: image["Id"].as_str().unwrap_or_default().to_string(),
+            tags: image["RepoTags"]
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Value::as_str)
+                .filter(|tag| *tag != "<none>:<none>")
+                .map(str::to_string)
+                .collect(),
+            size: image["Size"].as_u64().unwrap_or(0),
+            containers: image["Containers"].as_u64().unwrap_or(0),
+        })
+        .collect();
+    let containers = list("Containers");
+    let volumes = list("Volumes");
+    Ok(DiskUsage {
+        images,
+        containers: containers.len(),
+        containers_size: containers.iter().filter_map(|c| c["SizeRw"].as_u64()).sum(),
+        volumes: volumes.len(),
+        volumes_size: volumes
+            .iter()
+            .filter_map(|v| v["UsageData"]["Size"].as_u64())
+            .sum(),
+        build_cache_size: list("BuildCache")
+            .iter()
+            .filter(|c| c["InUse"] != true)
+            .filter_map(|c| c["Size"].as_u64())
+            .sum(),
+    })
+}
+
+/// What a prune action removes.
+#[derive(Clone, Copy, PartialEq)]
+pub enum PruneKind {
+    /// Stopped containers that are not Dev Containers.
+    Containers,
+    /// Untagged images left behind by rebuilds.
+    DanglingImages,
+    /// Tagged images no container uses, except protected ones.
+    UnusedImages,
+    /// Anonymous volumes no container uses.
+    Volumes,
+    BuildCache,
+}
+
+impl PruneKind {
+    pub const ALL: [PruneKind; 5] = [
+        PruneKind::Containers,
+        PruneKind::DanglingImages,
+        PruneKind::UnusedImages,
+        PruneKind::Volumes,
+        PruneKind::BuildCache,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PruneKind::Containers => "Stopped containers",
+            PruneKind::DanglingImages => "Dangling images",
+            PruneKind::UnusedImages => "Unused images",
+            PruneKind::Volumes => "Unused volumes",
+            PruneKind::BuildCache => "Build cache",
+        }
+    }
+}
+
+/// `docker.io/library/rust` and `rust` name the same image; a missing tag
+/// means `latest`.
+pub fn normalize_image(image: &str) -> String {
+    let image = image
+        .strip_prefix("docker.io/")
+        .unwrap_or(image)
+        .trim_start_matches("library/");
+    let (name, tag) = split_image(image);
+    if tag.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}:{}", name, tag)
+    }
+}
+
+fn space_reclaimed(body: &str) -> u64 {
+    serde_json::from_str::<Value>(body)
+        .map(|v| v["SpaceReclaimed"].as_u64().unwrap_or(0))
+        .unwrap_or(0)
+}
+
+/// Runs `kind` and returns the bytes freed. Images with a tag for which
+/// `is_protected` returns true are never removed.
+pub fn prune(kind: PruneKind, is_protected: &dyn Fn(&str) -> bool) -> Result<u64, String> {
+    match kind {
+        PruneKind::Containers => {
+            // {"label!":["devcontainer.local_folder"]}
+            let body = call(
+                "POST",
+                "/containers/prune?filters=%7B%22label!%22%3A%5B%22devcontainer.local_folder%22%5D%7D",
+            )?;
+            Ok(space_reclaimed(&body))
+        }
+        PruneKind::DanglingImages => {
+            call("POST", "/images/prune").map(|body| space_reclaimed(&body))
+        }
+        PruneKind::UnusedImages => {
+            let mut freed = 0;
+            for image in disk_usage()?.images {
+                if image.containers > 0
+                    || image.tags.is_empty()
+                    || image.tags.iter().any(|tag| is_protected(tag))
+                {
+                    continue;
+                }
+                // Removing by id fails for images with several tags, so untag each
+                match image.tags.iter().try_for_each(|tag| remove_image(tag)) {
+                    Ok(()) => freed += image.size,
+                    Err(e) => {
+                        crate::log_error(format!("Image {} kept: {}", image.tags.join(", "), e))
+                    }
+                }
+            }
+            Ok(freed)
+        }
+        PruneKind::Volumes => call("POST", "/volumes/prune").map(|body| space_reclaimed(&body)),
+        PruneKind::BuildCache => call("POST", "/build/prune").map(|body| space_reclaimed(&body)),
+    }
+}
+
+/// Sends a request and hands each line of the streamed response body to
+/// `on_line`. Returns the status code.
+fn stream(
+    endpoint: &Endpoint,
+    method: &str,
+    path: &str,
+    timeout: Duration,
+    on_line: &mut dyn FnMut(&str),
+) -> Result<u16, String> {
+    let mut stream =
+        connect(endpoint, timeout).map_err(|e| format!("Cannot connect to {}: {}", endpoint, e))?;
+    let request = format!(
+        "{} {} HTTP/1.1\r\nHost: docker\r\nConnection: close\r\nContent-Length: 0\r\n\r\n",
+        method, path
+    );
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| format!("Docker API request failed: {}", e))?;
+    let read_error = |e: std::io::Error| format!("Docker API response failed: {}", e);
+    let mut reader = BufReader::new(stream);
+
+    let mut status_line = String::new();
+    reader.read_line(&mut status_line).map_err(read_error)?;
+    let code = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or("Malformed Docker API status line")?;
+    let mut chunked = false;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).map_err(read_error)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        chunked |= header
+            .trim()
+            .eq_ignore_ascii_case("transfer-encoding: chunked");
+    }
+
+    let mut pending = String::new();
+    let mut emit = |data: &[u8], pending: &mut String| {
+        pending.push_str(&String::from_utf8_lossy(data));
+        while let Some(end) = pending.find('\n') {
+            let line: String = pending.drain(..=end).collect();
+            if !line.trim().is_empty() {
+                on_line(line.trim());
+            }
+        }
+    };
+    if chunked {
+        loop {
+            let mut size_line = String::new();
+            if reader.read_line(&mut size_line).map_err(read_error)? == 0 {
+                break;
+            }
+            let size = usize::from_str_radix(size_line.split(';').next().unwrap_or("").trim(), 16)
+                .unwrap_or(0);
+            if size == 0 {
+                break;
+            }
+            let mut chunk = vec![0; size + 2];
+            reader.read_exact(&mut chunk).map_err(read_error)?;
+            emit(&chunk[..size], &mut pending);
+        }
+    } else {
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body).map_err(read_error)?;
+        emit(&body, &mut pending);
+    }
+    if !pending.trim().is_empty() {
+        on_line(pending.trim());
+    }
+    Ok(code)
+}
+
+pub fn image_exists(image: &str) -> bool {
+    resolve()
+        .and_then(|(_, endpoint)| {
+            request(
+                &endpoint,
+                "GET",
+                &format!("/images/{}/json", image),
+                TIMEOUT,
+            )
+        })
+        .is_ok_and(|(code, _)| code == 200)
+}
+
+/// `fromImage` and `tag` query values. Without a tag the API pulls every
+/// tag, so `latest` is filled in like the docker CLI does.
+fn split_image(image: &str) -> (&str, &str) {
+    if image.contains('@') {
+        return (image, "");
+    }
+    let name_start = image.rfind('/').map_or(0, |i| i + 1);
+    match image[name_start..].rfind(':') {
+        Some(i) => (&image[..name_start + i], &image[name_start + i + 1..]),
+        None => (image, "latest"),
+    }
+}
+
+/// Pulls `image`, calling `on_progress` with a status text and the overall
+/// download percentage when the layer sizes are known.
+pub fn pull_image(
+    image: &str,
+    on_progress: &mut dyn FnMut(&str, Option<u8>),
+) -> Result<(), String> {
+    let (_, endpoint) = resolve()?;
+    let (name, tag) = split_image(image);
+    let path = format!("/images/create?fromImage={}&tag={}", name, tag);
+    let mut layers: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut error = None;
+    let code = stream(&endpoint, "POST", &path, PULL_TIMEOUT, &mut |line| {
+        let Ok(message) = serde_json::from_str::<Value>(line) else {
+            return;
+        };
+        // Errors before the pull starts come as `{"message": ...}`
+        if let Some(e) = message["error"].as_str().or(message["message"].as_str()) {
+            error = Some(e.to_string());
+            return;
+        }
+        let detail = &message["progressDetail"];
+        if let (Some(id), Some(current), Some(total)) = (
+            message["id"].as_str(),
+            detail["current"].as_u64(),
+            detail["total"].as_u64(),
+        ) {
+            layers.insert(id.to_string(), (current, total));
+        }
+        let (current, total) = layers
+            .values()
+            .fold((0, 0), |(c, t), (current, total)| (c + current, t + total));
+        let percent = (total > 0).then(|| (current * 100 / total).min(100) as u8);
+        on_progress(message["status"].as_str().unwrap_or_default(), percent);
+    })?;
+    if let Some(e) = error {
+        return Err(format!("Pull {} failed: {}", image, e));
+    }
+    if !(200..300).contains(&code) {
+        return Err(format!("Pull {} failed with status {}", image, code));
+    }
+    Ok(())
+}
+
+/// Queries `/info` on the resolved endpoint.
+pub fn status() -> DockerStatus {
+    let (context, endpoint) = match resolve() {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            return DockerStatus {
+                error: Some(e),
+                ..Default::default()
+            };
+        }
+    };
+    let mut status = DockerStatus {
+        context,
+        endpoint: endpoint.to_string(),
+        ..Default::default()
+    };
+    match get_json(&endpoint, "/info") {
+        Ok(info) => {
+            status.version = Some(
+                info["ServerVersion"]
+                    .as_str()
+                    .unwrap_or("unknown")
+                    .to_string(),
+            );
+            status.containers_running = info["ContainersRunning"].as_u64().unwrap_or(0);
+        }
+        Err(e) => status.error = Some(e),
+    }
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_chunked_joins_chunks() {
+        let body = b"4\r\nWiki\r\n6;ext=1\r\npedia \r\nE\r\nin \r\n\r\nchunks.\r\n0\r\n\r\n";
+        assert_eq!(decode_chunked(body), b"Wikipedia in \r\n\r\nchunks.");
+        // A truncated chunk ends the body instead of panicking
+        assert_eq!(decode_chunked(b"4\r\nWiki\r\nff\r\nshort"), b"Wiki");
+    }
+
+    #[test]
+    fn split_image_fills_in_latest() {
+        assert_eq!(split_image("rust"), ("rust", "latest"));
+        assert_eq!(split_image("rust:1.80"), ("rust", "1.80"));
+        assert_eq!(
+            split_image("localhost:5000/team/app"),
+            ("localhost:5000/team/app", "latest")
+        );
+        assert_eq!(
+            split_image("localhost:5000/team/app:v2"),
+            ("localhost:5000/team/app", "v2")
+        );
+        assert_eq!(split_image("rust@sha256:ab"), ("rust@sha256:ab", ""));
+    }
+
+    #[test]
+    fn normalize_image_drops_default_registry() {
+        assert_eq!(normalize_image("docker.io/library/rust"), "rust:latest");
+        assert_eq!(normalize_image("library/rust:1.80"), "rust:1.80");
+        assert_eq!(normalize_image("ghcr.io/org/img:1"), "ghcr.io/org/img:1");
+    }
+}
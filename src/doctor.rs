@@ -0,0 +1,397 @@
+use std::io::Write as _;
+
+use crate::{cmd, docker, editor, engine, parameter, settings, template_cache, templates, udev};
+
+#[derive(Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, serde::Serialize)]
+pub struct DoctorCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to do about a warning or failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn new(name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// Result of [`run`], exported for support requests.
+#[derive(Clone, PartialEq, serde::Serialize)]
+pub struct DoctorReport {
+    pub app_version: String,
+    pub os: String,
+    pub generated_at: String,
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!(
+            "# {} Doctor\n\n- Version: {}\n- OS: {}\n- Generated: {}\n\n| Check | Status | Detail | Hint |\n|---|---|---|---|\n",
+            parameter::APP_NAME,
+            self.app_version,
+            self.os,
+            self.generated_at
+        );
+        let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+        for check in &self.checks {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                cell(&check.name),
+                check.status.label(),
+                cell(&check.detail),
+                cell(check.hint.as_deref().unwrap_or(""))
+            ));
+        }
+        md
+    }
+}
+
+/// Runs every check. Blocking: call it from a worker thread in the UI.
+pub fn run() -> DoctorReport {
+    let s = settings::load();
+    DoctorReport {
+        app_version: parameter::build_version_label(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        generated_at: chrono::Local::now().to_rfc3339(),
+        checks: vec![
+            check_engine(),
+            check_editor(s.editor),
+            check_probe(),
+            check_usb_permissions(),
+            check_dap_port(&s.dap_ip, s.dap_port),
+            check_template(&s),
+            check_config_dir(),
+        ],
+    }
+}
+
+fn check_engine() -> DoctorCheck {
+    const NAME: &str = "Container engine";
+    let engine = engine::selected();
+    let status = docker::status();
+    if let Some(version) = &status.version {
+        return DoctorCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            format!(
+                "{} running, Docker {} (context {})",
+                engine.name(),
+                version,
+                status.context
+            ),
+        );
+    }
+    let reason = status.error.unwrap_or_default();
+    if engine.is_installed() {
+        DoctorCheck::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} is not running: {}", engine.name(), reason),
+        )
+        .hint(format!(
+            "Start {} from Settings → Docker Engine.",
+            engine.name()
+        ))
+    } else {
+        DoctorCheck::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} was not found: {}", engine.name(), reason),
+        )
+        .hint("Install Rancher Desktop (https://rancherdesktop.io/) or pick the installed engine in Settings → Docker Engine.")
+    }
+}
+
+fn check_editor(selected: editor::Editor) -> DoctorCheck {
+    const NAME: &str = "Editor and extensions";
+    let check = editor::check_extensions(selected);
+    let label = check.editor.label();
+    if let Some(error) = check.error {
+        return DoctorCheck::new(NAME, CheckStatus::Fail, error).hint(format!(
+            "Install {} and its `{}` command.",
+            label,
+            check.editor.clis().join(" / ")
+        ));
+    }
+    let missing: Vec<String> = check
+        .extensions
+        .iter()
+        .filter(|(_, _, installed)| !installed)
+        .map(|(_, name, _)| name.clone())
+        .collect();
+    if missing.is_empty() {
+        DoctorCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("{} with all required extensions", label),
+        )
+    } else {
+        DoctorCheck::new(
+            NAME,
+            CheckStatus::Warn,
+            format!("{} is missing: {}", label, missing.join(", ")),
+        )
+        .hint("Install them from Settings → Editor.")
+    }
+}
+
+fn check_probe() -> DoctorCheck {
+    const NAME: &str = "Debug probe";
+    let probes = cmd::list_probes();
+    if !probes.is_empty() {
+        return DoctorCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            probes
+                .iter()
+                .map(|p| format!("{} ({})", p.identifier, p.selector()))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+    }
+    let denied = udev::denied_devices();
+    if denied.is_empty() {
+        DoctorCheck::new(NAME, CheckStatus::Warn, "No debug probe found")
+            .hint("Connect the Baker link board with a USB cable that carries data.")
+    } else {
+        DoctorCheck::new(NAME, CheckStatus::Fail, udev::explain(&denied)).hint(udev_hint())
+    }
+}
+
+fn check_usb_permissions() -> DoctorCheck {
+    const NAME: &str = "USB permissions";
+    if !cfg!(target_os = "linux") {
+        return DoctorCheck::new(NAME, CheckStatus::Pass, "No udev rules needed on this OS");
+    }
+    let denied = udev::denied_devices();
+    match (udev::installed_rule(), denied.is_empty()) {
+        (Some(path), true) => DoctorCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("udev rule found: {}", path.display()),
+        ),
+        (rule, false) => DoctorCheck::new(
+            NAME,
+            CheckStatus::Fail,
+            format!(
+                "Permission denied: {}{}",
+                denied
+                    .iter()
+                    .map(udev::DeniedDevice::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                rule.map(|path| format!(
+                    " (rule {} is installed; replug the board or log in again)",
+                    path.display()
+                ))
+                .unwrap_or_default()
+            ),
+        )
+        .hint(udev_hint()),
+        (None, true) => DoctorCheck::new(
+            NAME,
+            CheckStatus::Warn,
+            format!(
+                "No udev rule for the Baker link probe ({:04x}:{:04x})",
+                parameter::BAKER_LINK_VID,
+                parameter::BAKER_LINK_PID
+            ),
+        )
+        .hint(udev_hint()),
+    }
+}
+
+fn udev_hint() -> String {
+    format!(
+        "Create {} with Doctor → USB permissions or `{} doctor --udev-rules`, then run: {}",
+        udev::RULES_FILE,
+        env!("CARGO_PKG_NAME"),
+        udev::install_command("sudo", &udev::generated_rules_path())
+    )
+}
+
+fn check_dap_port(ip: &str, port: u16) -> DoctorCheck {
+    const NAME: &str = "DAP port";
+    let running = crate::dap_server()
+        .lock()
+        .is_ok_and(|server| server.status == cmd::DapServerStatus::Running(port));
+    if running {
+        return DoctorCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("{}:{} is used by the DAP server", ip, port),
+        );
+    }
+    match std::net::TcpListener::bind((ip, port)) {
+        Ok(_) => DoctorCheck::new(NAME, CheckStatus::Pass, format!("{}:{} is free", ip, port)),
+        Err(e) => DoctorCheck::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{}:{} cannot be used: {}", ip, port, e),
+        )
+        .hint("Close the program using the port or pick another port in Settings → DAP Server."),
+    }
+}
+
+fn check_template(s: &settings::AppSettings) -> DoctorCheck {
+    const NAME: &str = "Template";
+    let template = templates::find(&templates::catalog(), &s.selected_template);
+    let cached = template_cache::find_cached(&template).is_some();
+    let reachable = match &template.source {
+        templates::TemplateSource::Path(path) => std::path::Path::new(path)
+            .is_dir()
+            .then_some(())
+            .ok_or_else(|| format!("{} does not exist", path)),
+        templates::TemplateSource::Git(_) if s.offline_mode => {
+            Err("offline mode is on".to_string())
+        }
+        templates::TemplateSource::Git(_) if cmd::require_git().is_err() => {
+            return DoctorCheck::new(
+                NAME,
+                CheckStatus::Warn,
+                format!("Git is not installed, so {} cannot be checked or cached", template.name),
+            )
+            .hint("Install Git from https://git-scm.com/. Projects can still be generated while online.");
+        }
+        templates::TemplateSource::Git(url) => cmd::git_ls_remote(url),
+    };
+    match (reachable, cached) {
+        (Ok(()), _) => DoctorCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("{} is reachable", template.name),
+        ),
+        (Err(e), true) => DoctorCheck::new(
+            NAME,
+            CheckStatus::Warn,
+            format!("{} is not reachable, the cached copy will be used: {}", template.name, e),
+        )
+        .hint("Check the network or proxy settings."),
+        (Err(e), false) => DoctorCheck::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} is not reachable: {}", template.name, e),
+        )
+        .hint("Check the network or proxy settings, or import a template archive in the template manager."),
+    }
+}
+
+fn check_config_dir() -> DoctorCheck {
+    const NAME: &str = "Config folder";
+    let dir = settings::config_dir();
+    let probe = dir.join(".doctor-write-test");
+    let result = std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::File::create(&probe))
+        .and_then(|mut file| file.write_all(b"ok"));
+    let _ = std::fs::remove_file(&probe);
+    match result {
+        Ok(()) => DoctorCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("{} is writable", dir.display()),
+        ),
+        Err(e) => DoctorCheck::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} is not writable: {}", dir.display(), e),
+        )
+        .hint("Fix the folder permissions; settings and history cannot be saved."),
+    }
+}
+
+/// `doctor [--json] [--output <file>]` or `doctor --udev-rules`. Returns the
+/// process exit code: 1 when a check failed.
+pub fn cli(args: &[String]) -> i32 {
+    #[cfg(target_os = "windows")]
+    attach_console();
+    if args.iter().any(|a| a == "--udev-rules") {
+        return match udev::write_rules() {
+            Ok(path) => {
+                println!("Wrote {}. Install it with:", path.display());
+                println!("  {}", udev::install_command("sudo", &path));
+                0
+            }
+            Err(e) => {
+                eprintln!("Failed to write udev rules: {}", e);
+                2
+            }
+        };
+    }
+    let json = args.iter().any(|a| a == "--json");
+    let output = args
+        .iter()
+        .position(|a| a == "--output" || a == "-o")
+        .and_then(|i| args.get(i + 1));
+    let report = run();
+    let text = if json {
+        report.to_json()
+    } else {
+        report.to_markdown()
+    };
+    match output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, &text) {
+                eprintln!("Failed to write {}: {}", path, e);
+                return 2;
+            }
+        }
+        None => println!("{}", text),
+    }
+    i32::from(report.count(CheckStatus::Fail) > 0)
+}
+
+/// Release builds use the GUI subsystem and start without a console, so
+/// print into the terminal that started us instead.
+#[cfg(target_os = "windows")]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // SAFETY: AttachConsole takes a plain process id; when it fails (no parent
+    // console, or one already attached) output goes nowhere, as before.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
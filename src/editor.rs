@@ -0,0 +1,378 @@
+use std::path::{Path, PathBuf};
+
+use crate::cmd;
+
+/// Placeholder replaced with the project folder in a custom editor command.
+pub const PATH_PLACEHOLDER: &str = "{path}";
+
+/// Extensions the Dev Container workflow needs, as (id, name).
+const REQUIRED_EXTENSIONS: &[(&str, &str)] = &[
+    ("ms-vscode-remote.remote-containers", "Dev Containers"),
+    ("probe-rs.probe-rs-debugger", "probe-rs debugger"),
+    ("rust-lang.rust-analyzer", "rust-analyzer"),
+];
+
+/// VSCodium installs from Open VSX, which does not carry Microsoft's Dev
+/// Containers extension.
+const OPEN_VSX_EXTENSIONS: &[(&str, &str)] = &[
+    ("probe-rs.probe-rs-debugger", "probe-rs debugger"),
+    ("rust-lang.rust-analyzer", "rust-analyzer"),
+];
+
+/// Cursor installs from Open VSX too and ships its own Dev Containers
+/// extension, so Microsoft's cannot be installed there.
+const CURSOR_EXTENSIONS: &[(&str, &str)] = OPEN_VSX_EXTENSIONS;
+
+/// Editor used to open projects.
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Editor {
+    #[default]
+    VsCode,
+    VsCodium,
+    Cursor,
+    Zed,
+    RustRover,
+    /// Runs `nvim` in a new terminal window.
+    Neovim,
+    /// `AppSettings::editor_command`, with `{path}` replaced by the project folder.
+    Custom,
+}
+
+impl Editor {
+    pub const ALL: [Editor; 7] = [
+        Editor::VsCode,
+        Editor::VsCodium,
+        Editor::Cursor,
+        Editor::Zed,
+        Editor::RustRover,
+        Editor::Neovim,
+        Editor::Custom,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Editor::VsCode => "VS Code",
+            Editor::VsCodium => "VSCodium",
+            Editor::Cursor => "Cursor",
+            Editor::Zed => "Zed",
+            Editor::RustRover => "RustRover",
+            Editor::Neovim => "Neovim",
+            Editor::Custom => "Custom command",
+        }
+    }
+
+    /// Value stored in settings.json and used by the Settings view select.
+    pub fn id(self) -> &'static str {
+        match self {
+            Editor::VsCode => "vscode",
+            Editor::VsCodium => "vscodium",
+            Editor::Cursor => "cursor",
+            Editor::Zed => "zed",
+            Editor::RustRover => "rustrover",
+            Editor::Neovim => "neovim",
+            Editor::Custom => "custom",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|editor| editor.id() == id)
+            .unwrap_or_default()
+    }
+
+    /// Command-line launchers, in the order they are tried.
+    pub fn clis(self) -> &'static [&'static str] {
+        match self {
+            Editor::VsCode => &["code"],
+            Editor::VsCodium => &["codium"],
+            Editor::Cursor => &["cursor"],
+            Editor::Zed => &["zed", "zeditor"],
+            Editor::RustRover => &["rustrover", "rustrover64"],
+            Editor::Neovim => &["nvim"],
+            Editor::Custom => &[],
+        }
+    }
+
+    /// Application bundle name used with `open -a` on macOS.
+    pub fn mac_app(self) -> Option<&'static str> {
+        match self {
+            Editor::VsCode => Some("Visual Studio Code"),
+            Editor::VsCodium => Some("VSCodium"),
+            Editor::Cursor => Some("Cursor"),
+            Editor::Zed => Some("Zed"),
+            Editor::RustRover => Some("RustRover"),
+            Editor::Neovim | Editor::Custom => None,
+        }
+    }
+
+    /// Editors that understand `--folder-uri vscode-remote://dev-container+…`.
+    pub fn supports_dev_container(self) -> bool {
+        matches!(self, Editor::VsCode | Editor::Cursor)
+    }
+
+    /// Editors whose CLI supports `--list-extensions` / `--install-extension`.
+    pub fn supports_extensions(self) -> bool {
+        matches!(self, Editor::VsCode | Editor::VsCodium | Editor::Cursor)
+    }
+
+    /// Extensions [`check_extensions`] looks for, as (id, name).
+    pub fn required_extensions(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Editor::VsCode => REQUIRED_EXTENSIONS,
+            Editor::VsCodium => OPEN_VSX_EXTENSIONS,
+            Editor::Cursor => CURSOR_EXTENSIONS,
+            Editor::Zed | Editor::RustRover | Editor::Neovim | Editor::Custom => &[],
+        }
+    }
+
+    /// Launcher found on `PATH`, or in the usual install folders that GUI
+    /// installers do not add to `PATH`.
+    pub fn find_cli(self) -> Option<PathBuf> {
+        self.clis()
+            .iter()
+            .find_map(|cli| cmd::find_in_path(cli))
+            .or_else(|| {
+                self.install_locations()
+                    .into_iter()
+                    .find(|candidate| candidate.is_file())
+            })
+    }
+
+    fn install_locations(self) -> Vec<PathBuf> {
+        #[cfg(target_os = "windows")]
+        {
+            let local = std::env::var("LOCALAPPDATA").unwrap_or_default();
+            let program_files = std::env::var("ProgramFiles").unwrap_or_default();
+            let (folder, bin) = match self {
+                Editor::VsCode => ("Microsoft VS Code", "bin\\code.cmd"),
+                Editor::VsCodium => ("VSCodium", "bin\\codium.cmd"),
+                Editor::Cursor => ("cursor", "resources\\app\\bin\\cursor.cmd"),
+                Editor::Zed => ("Zed", "zed.exe"),
+                _ => return Vec::new(),
+            };
+            vec![
+                Path::new(&local).join("Programs").join(folder).join(bin),
+                Path::new(&program_files).join(folder).join(bin),
+            ]
+        }
+        #[cfg(target_os = "macos")]
+        {
+            let (Some(app), Some(cli)) = (self.mac_app(), self.clis().first()) else {
+                return Vec::new();
+            };
+            let home = std::env::var("HOME").unwrap_or_default();
+            let bin = match self {
+                Editor::Zed => "Contents/MacOS/cli".to_string(),
+                _ => format!("Contents/Resources/app/bin/{cli}"),
+            };
+            vec![
+                Path::new("/Applications")
+                    .join(format!("{app}.app"))
+                    .join(&bin),
+                Path::new(&home)
+                    .join("Applications")
+                    .join(format!("{app}.app"))
+                    .join(&bin),
+            ]
+        }
+        #[cfg(target_os = "linux")]
+        {
+            let home = std::env::var("HOME").unwrap_or_default();
+            let (flatpak, extra): (&str, &[&str]) = match self {
+                Editor::VsCode => (
+                    "com.visualstudio.code",
+                    &["/usr/share/code/bin/code", "/snap/bin/code"],
+                ),
+                Editor::VsCodium => (
+                    "com.vscodium.codium",
+                    &["/usr/share/codium/bin/codium", "/snap/bin/codium"],
+                ),
+                Editor::Zed => ("dev.zed.Zed", &[]),
+                _ => return Vec::new(),
+            };
+            let mut locations: Vec<PathBuf> = extra.iter().map(PathBuf::from).collect();
+            locations.push(Path::new("/var/lib/flatpak/exports/bin").join(flatpak));
+            locations.push(
+                Path::new(&home)
+                    .join(".local/share/flatpak/exports/bin")
+                    .join(flatpak),
+            );
+            locations
+        }
+    }
+
+    pub fn is_installed(self) -> bool {
+        if self.find_cli().is_some() {
+            return true;
+        }
+        if cfg!(target_os = "macos") {
+            if let Some(app) = self.mac_app() {
+                let bundle = format!("{app}.app");
+                let home = std::env::var("HOME").unwrap_or_default();
+                return Path::new("/Applications").join(&bundle).exists()
+                    || Path::new(&home).join("Applications").join(&bundle).exists();
+            }
+        }
+        false
+    }
+}
+
+/// Result of [`check_extensions`] for the Settings view.
+#[derive(Clone, PartialEq)]
+pub struct ExtensionCheck {
+    pub editor: Editor,
+    pub cli: Option<PathBuf>,
+    /// (id, name, installed) for each of [`Editor::required_extensions`].
+    pub extensions: Vec<(String, String, bool)>,
+    pub error: Option<String>,
+}
+
+impl ExtensionCheck {
+    pub fn missing(&self) -> Vec<String> {
+        self.extensions
+            .iter()
+            .filter(|(_, _, installed)| !installed)
+            .map(|(id, _, _)| id.clone())
+            .collect()
+    }
+}
+
+/// The editor whose extensions are checked: the configured one when it is
+/// VS Code compatible, VS Code otherwise.
+pub fn extension_host(editor: Editor) -> Editor {
+    if editor.supports_extensions() {
+        editor
+    } else {
+        Editor::VsCode
+    }
+}
+
+/// Finds the CLI of `editor` and compares its installed extensions with
+/// [`Editor::required_extensions`].
+pub fn check_extensions(editor: Editor) -> ExtensionCheck {
+    let editor = extension_host(editor);
+    let cli = editor.find_cli();
+    let listed = match &cli {
+        Some(cli) => cmd::list_editor_extensions(cli),
+        None => Err(format!(
+            "{} command-line launcher ({}) was not found",
+            editor.label(),
+            editor.clis().join(" / ")
+        )),
+    };
+    let (installed, error) = match listed {
+        Ok(ids) => (ids, None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+    ExtensionCheck {
+        editor,
+        cli,
+        extensions: editor
+            .required_extensions()
+            .iter()
+            .map(|(id, name)| {
+                let found = installed.iter().any(|i| i.eq_ignore_ascii_case(id));
+                (id.to_string(), name.to_string(), found)
+            })
+            .collect(),
+        error,
+    }
+}
+
+/// Editors found on this machine. Custom is always available.
+pub fn detect_installed() -> Vec<Editor> {
+    Editor::ALL
+        .into_iter()
+        .filter(|editor| *editor == Editor::Custom || editor.is_installed())
+        .collect()
+}
+
+/// Splits a custom command template into program and arguments, replacing
+/// `{path}`. Double quotes group words; the path is appended when the
+/// template has no placeholder.
+pub fn expand_command(template: &str, path: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quoted = false;
+    for c in template.trim().chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_word = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quoted {
+        return Err("Custom editor command has an unclosed quote".to_string());
+    }
+    if in_word {
+        args.push(current);
+    }
+    if args.is_empty() {
+        return Err("Custom editor command is empty".to_string());
+    }
+    if !template.contains(PATH_PLACEHOLDER) {
+        args.push(path.to_string());
+    }
+    Ok(args
+        .into_iter()
+        .map(|arg| arg.replace(PATH_PLACEHOLDER, path))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_replaces_placeholder() {
+        assert_eq!(
+            expand_command("subl -n {path}", "/p").unwrap(),
+            ["subl", "-n", "/p"]
+        );
+        assert_eq!(
+            expand_command("\"C:\\Program Files\\ed.exe\" --dir={path}", "C:\\p q").unwrap(),
+            ["C:\\Program Files\\ed.exe", "--dir=C:\\p q"]
+        );
+    }
+
+    #[test]
+    fn expand_appends_path_without_placeholder() {
+        assert_eq!(expand_command("  kate  ", "/p").unwrap(), ["kate", "/p"]);
+        assert_eq!(expand_command("ed \"\"", "/p").unwrap(), ["ed", "", "/p"]);
+    }
+
+    #[test]
+    fn expand_rejects_bad_templates() {
+        assert!(expand_command("   ", "/p").is_err());
+        assert!(expand_command("\"ed {path}", "/p").is_err());
+    }
+
+    #[test]
+    fn open_vsx_editors_do_not_need_dev_containers() {
+        let ids = |editor: Editor| {
+            editor
+                .required_extensions()
+                .iter()
+                .map(|(id, _)| *id)
+                .collect::<Vec<_>>()
+        };
+        assert!(ids(Editor::VsCode).contains(&"ms-vscode-remote.remote-containers"));
+        assert!(!ids(Editor::VsCodium).contains(&"ms-vscode-remote.remote-containers"));
+        assert!(!ids(Editor::Cursor).contains(&"ms-vscode-remote.remote-containers"));
+        assert!(ids(Editor::Cursor).contains(&"probe-rs.probe-rs-debugger"));
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{cmd, docker, settings};
+
+/// Container engine selected in Settings. `Auto` picks the first installed one.
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EngineKind {
+    #[default]
+    Auto,
+    RancherDesktop,
+    DockerEngine,
+    Podman,
+    Colima,
+}
+
+impl EngineKind {
+    pub const ALL: [EngineKind; 5] = [
+        EngineKind::Auto,
+        EngineKind::RancherDesktop,
+        EngineKind::DockerEngine,
+        EngineKind::Podman,
+        EngineKind::Colima,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EngineKind::Auto => "Auto-detect",
+            EngineKind::RancherDesktop => "Rancher Desktop",
+            EngineKind::DockerEngine => "Docker Engine",
+            EngineKind::Podman => "Podman",
+            EngineKind::Colima => "Colima",
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            EngineKind::Auto => "auto",
+            EngineKind::RancherDesktop => "rancher-desktop",
+            EngineKind::DockerEngine => "docker-engine",
+            EngineKind::Podman => "podman",
+            EngineKind::Colima => "colima",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.id() == id)
+            .unwrap_or_default()
+    }
+}
+
+/// Starts and checks the engine that provides Docker for the Dev Containers.
+pub trait ContainerEngine: Send + Sync {
+    fn kind(&self) -> EngineKind;
+    fn is_installed(&self) -> bool;
+    fn is_running(&self) -> Result<bool, String>;
+    fn start(&self) -> Result<(), String>;
+    fn stop(&self) -> Result<(), String>;
+
+    fn name(&self) -> &'static str {
+        self.kind().label()
+    }
+}
+
+pub struct RancherDesktop;
+
+impl ContainerEngine for RancherDesktop {
+    fn kind(&self) -> EngineKind {
+        EngineKind::RancherDesktop
+    }
+
+    fn is_installed(&self) -> bool {
+        cmd::find_engine_cli("rdctl").is_some()
+    }
+
+    fn is_running(&self) -> Result<bool, String> {
+        Ok(docker::status().is_up())
+    }
+
+    fn start(&self) -> Result<(), String> {
+        cmd::engine_spawn("rdctl", &["start", "--application.start-in-background"])
+    }
+
+    fn stop(&self) -> Result<(), String> {
+        cmd::engine_run("rdctl", &["shutdown"])
+    }
+}
+
+/// Native dockerd managed by systemd (Linux only).
+pub struct DockerEngine;
+
+impl ContainerEngine for DockerEngine {
+    fn kind(&self) -> EngineKind {
+        EngineKind::DockerEngine
+    }
+
+    fn is_installed(&self) -> bool {
+        cfg!(target_os = "linux") && cmd::find_engine_cli("dockerd").is_some()
+    }
+
+    fn is_running(&self) -> Result<bool, String> {
+        Ok(docker::status().is_up())
+    }
+
+    fn start(&self) -> Result<(), String> {
+        if !cfg!(target_os = "linux") {
+            return Err("Docker Engine via systemd is only available on Linux".to_string());
+        }
+        // pkexec shows the desktop's password prompt for the system service
+        cmd::engine_run("pkexec", &["systemctl", "start", "docker"])
+    }
+
+    fn stop(&self) -> Result<(), String> {
+        if !cfg!(target_os = "linux") {
+            return Err("Docker Engine via systemd is only available on Linux".to_string());
+        }
+        // docker.socket would start the daemon again on the next request
+        cmd::engine_run("pkexec", &["systemctl", "stop", "docker.socket", "docker"])
+    }
+}
+
+pub struct Podman;
+
+impl ContainerEngine for Podman {
+    fn kind(&self) -> EngineKind {
+        EngineKind::Podman
+    }
+
+    fn is_installed(&self) -> bool {
+        cmd::find_engine_cli("podman").is_some()
+    }
+
+    fn is_running(&self) -> Result<bool, String> {
+        cmd::engine_probe("podman", &["info", "--format", "{{.Version.Version}}"])
+    }
+
+    fn start(&self) -> Result<(), String> {
+        if cfg!(target_os = "linux") {
+            // Rootless API socket used by the docker CLI and Dev Containers
+            cmd::engine_run("systemctl", &["--user", "start", "podman.socket"])
+        } else {
+            cmd::engine_spawn("podman", &["machine", "start"])
+        }
+    }
+
+    fn stop(&self) -> Result<(), String> {
+        if cfg!(target_os = "linux") {
+            cmd::engine_run("systemctl", &["--user", "stop", "podman.socket"])
+        } else {
+            cmd::engine_run("podman", &["machine", "stop"])
+        }
+    }
+}
+
+pub struct Colima;
+
+impl ContainerEngine for Colima {
+    fn kind(&self) -> EngineKind {
+        EngineKind::Colima
+    }
+
+    fn is_installed(&self) -> bool {
+        !cfg!(target_os = "windows") && cmd::find_engine_cli("colima").is_some()
+    }
+
+    fn is_running(&self) -> Result<bool, String> {
+        Ok(cmd::engine_run("colima", &["status"]).is_ok())
+    }
+
+    fn start(&self) -> Result<(), String> {
+        cmd::engine_spawn("colima", &["start"])
+    }
+
+    fn stop(&self) -> Result<(), String> {
+        cmd::engine_run("colima", &["stop"])
+    }
+}
+
+/// Engines tried by auto-detection, most common on each OS first.
+fn detection_order() -> &'static [EngineKind] {
+    if cfg!(target_os = "linux") {
+        &[
+            EngineKind::DockerEngine,
+            EngineKind::Podman,
+            EngineKind::RancherDesktop,
+            EngineKind::Colima,
+        ]
+    } else {
+        &[
+            EngineKind::RancherDesktop,
+            EngineKind::Colima,
+            EngineKind::Podman,
+        ]
+    }
+}
+
+/// First installed engine, or Rancher Desktop when none is found.
+pub fn detect() -> EngineKind {
+    detection_order()
+        .iter()
+        .copied()
+        .find(|kind| engine(*kind).is_installed())
+        .unwrap_or(EngineKind::RancherDesktop)
+}
+
+pub fn engine(kind: EngineKind) -> Box<dyn ContainerEngine> {
+    match kind {
+        EngineKind::Auto => engine(detect()),
+        EngineKind::RancherDesktop => Box::new(RancherDesktop),
+        EngineKind::DockerEngine => Box::new(DockerEngine),
+        EngineKind::Podman => Box::new(Podman),
+        EngineKind::Colima => Box::new(Colima),
+    }
+}
+
+/// The engine chosen in Settings, resolving `Auto`.
+pub fn selected() -> Box<dyn ContainerEngine> {
+    engine(settings::load().container_engine)
+}
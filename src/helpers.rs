@@ -2,6 +2,10 @@ use dioxus::desktop::tao::window::Icon;
 
 use base64::Engine as _;
 
+use crate::docker::DockerStatus;
+use crate::doctor::CheckStatus;
+use crate::project::DevContainerState;
+
 pub fn load_window_icon() -> Icon {
     let bytes = include_bytes!("../icon/icon.png");
     let img = image::load_from_memory(bytes)
@@ -16,16 +20,33 @@ pub fn logo_data_uri() -> String {
     format!("data:image/png;base64,{b64}")
 }
 
-pub fn docker_dot_class(status: &str) -> &'static str {
-    if status.contains("On") {
+pub fn docker_dot_class(status: &DockerStatus) -> &'static str {
+    if status.is_up() {
         "status-dot status-dot-green"
-    } else if status.contains("Off") {
+    } else if status.is_down() {
         "status-dot status-dot-red"
     } else {
         "status-dot status-dot-gray"
     }
 }
 
+pub fn container_state_class(state: DevContainerState) -> &'static str {
+    match state {
+        DevContainerState::Running => "container-badge-running",
+        DevContainerState::Stopped => "container-badge-stopped",
+        DevContainerState::Stale => "container-badge-stale",
+        DevContainerState::Missing => "container-badge-missing",
+    }
+}
+
+pub fn check_status_class(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Pass => "doctor-badge doctor-pass",
+        CheckStatus::Warn => "doctor-badge doctor-warn",
+        CheckStatus::Fail => "doctor-badge doctor-fail",
+    }
+}
+
 pub fn dap_dot_class(running: bool) -> &'static str {
     if running {
         "status-dot status-dot-pulse"
@@ -33,3 +54,19 @@ pub fn dap_dot_class(running: bool) -> &'static str {
         "status-dot status-dot-gray"
     }
 }
+
+/// `1.4 GB`-style size for the disk usage panel.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
@@ -0,0 +1,475 @@
+use serde::Serialize;
+use serde_json::Value;
+
+/// Indentation for values inserted by [`set`] and [`push`], as VS Code uses.
+const INDENT: &str = "    ";
+
+/// A step into nested JSON: an object member or an array element.
+#[derive(Clone, Copy)]
+pub enum Seg<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Parses VS Code style JSON, which may contain comments and trailing commas.
+pub fn parse(text: &str) -> Result<Value, String> {
+    serde_json::from_str(&strip(text)).map_err(|e| e.to_string())
+}
+
+/// Removes comments, then trailing commas, leaving plain JSON.
+pub fn strip(text: &str) -> String {
+    remove_trailing_commas(&remove_comments(text))
+}
+
+fn remove_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        out.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for skipped in chars.by_ref() {
+                    if skipped == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = '\0';
+                for skipped in chars.by_ref() {
+                    if prev == '*' && skipped == '/' {
+                        break;
+                    }
+                    prev = skipped;
+                }
+                // Keep `1/**/2` two tokens
+                out.push(' ');
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Drops commas followed only by whitespace and `}` or `]`. Runs after
+/// [`remove_comments`], so a comment between the comma and the bracket
+/// no longer hides it.
+fn remove_trailing_commas(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        out.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            ',' => {
+                let next = chars.clone().find(|ch| !ch.is_whitespace());
+                if !matches!(next, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Sets `key` in the object at `path` to `value`. Only the value's text is
+/// replaced, or a new member is inserted, so comments and layout elsewhere
+/// in the file are kept.
+pub fn set(text: &str, path: &[Seg], key: &str, value: &Value) -> Result<String, String> {
+    let object = locate(text, path)?;
+    let (members, close) = members(text, object)?;
+    if let Some(member) = members.iter().find(|m| m.key == key) {
+        let rendered = render(value, line_indent(text, member.key_start), newline(text));
+        return Ok(splice(
+            text,
+            member.value_start,
+            member.value_end,
+            &rendered,
+        ));
+    }
+    let nl = newline(text);
+    let indent = match members.first() {
+        Some(first) if text[object..first.key_start].contains('\n') => {
+            line_indent(text, first.key_start).to_string()
+        }
+        Some(_) => format!("{}{}", line_indent(text, object), INDENT),
+        None => format!("{}{}", line_indent(text, close), INDENT),
+    };
+    let mut inserted = format!(
+        "{nl}{indent}{}: {}",
+        Value::from(key),
+        render(value, &indent, nl)
+    );
+    if !members.is_empty() {
+        inserted.push(',');
+    } else if text[object + 1..close].trim().is_empty() {
+        inserted.push_str(nl);
+        inserted.push_str(line_indent(text, close));
+        return Ok(splice(text, object + 1, close, &inserted));
+    }
+    Ok(splice(text, object + 1, object + 1, &inserted))
+}
+
+/// Removes `key` from the object at `path`, if present.
+pub fn remove(text: &str, path: &[Seg], key: &str) -> Result<String, String> {
+    let object = locate(text, path)?;
+    let (members, _) = members(text, object)?;
+    let Some(index) = members.iter().position(|m| m.key == key) else {
+        return Ok(text.to_string());
+    };
+    let member = &members[index];
+    let (start, end) = if let Some(next) = members.get(index + 1) {
+        (member.key_start, next.key_start)
+    } else {
+        let start = match index.checked_sub(1) {
+            Some(prev) => members[prev].value_end,
+            None => member.key_start,
+        };
+        // A trailing comma of the only member would be left dangling
+        let after = skip_trivia(text.as_bytes(), member.value_end);
+        if index == 0 && text.as_bytes().get(after) == Some(&b',') {
+            (start, after + 1)
+        } else {
+            (start, member.value_end)
+        }
+    };
+    Ok(splice(text, start, end, ""))
+}
+
+/// Appends `value` to the array at `path`.
+pub fn push(text: &str, path: &[Seg], value: &Value) -> Result<String, String> {
+    let array = locate(text, path)?;
+    let (elements, close) = elements(text, array)?;
+    let nl = newline(text);
+    match elements.last() {
+        Some(&(start, end)) => {
+            let (separator, indent) = if text[array..start].contains('\n') {
+                let indent = line_indent(text, start);
+                (format!("{nl}{indent}"), indent)
+            } else {
+                (" ".to_string(), line_indent(text, array))
+            };
+            let inserted = format!(",{separator}{}", render(value, indent, nl));
+            Ok(splice(text, end, end, &inserted))
+        }
+        None => {
+            let close_indent = line_indent(text, close);
+            let indent = format!("{close_indent}{INDENT}");
+            let inserted = format!(
+                "[{nl}{indent}{}{nl}{close_indent}]",
+                render(value, &indent, nl)
+            );
+            Ok(splice(text, array, close + 1, &inserted))
+        }
+    }
+}
+
+struct Member {
+    key: String,
+    key_start: usize,
+    value_start: usize,
+    value_end: usize,
+}
+
+fn splice(text: &str, start: usize, end: usize, replacement: &str) -> String {
+    format!("{}{}{}", &text[..start], replacement, &text[end..])
+}
+
+fn newline(text: &str) -> &'static str {
+    if text.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// Leading whitespace of the line that contains `pos`.
+fn line_indent(text: &str, pos: usize) -> &str {
+    let line_start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[line_start..pos];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+/// Pretty-prints `value` so that it continues a line indented by `indent`.
+fn render(value: &Value, indent: &str, nl: &str) -> String {
+    let mut buf = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(INDENT.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buf, formatter);
+    // Serializing a Value into memory cannot fail
+    let _ = value.serialize(&mut serializer);
+    String::from_utf8_lossy(&buf).replace('\n', &format!("{nl}{indent}"))
+}
+
+fn skip_trivia(b: &[u8], mut i: usize) -> usize {
+    loop {
+        while i < b.len() && b[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if b[i..].starts_with(b"//") {
+            while i < b.len() && b[i] != b'\n' {
+                i += 1;
+            }
+        } else if b[i..].starts_with(b"/*") {
+            i = b[i + 2..]
+                .windows(2)
+                .position(|w| w == b"*/")
+                .map_or(b.len(), |end| i + 2 + end + 2);
+        } else {
+            return i;
+        }
+    }
+}
+
+fn string_end(b: &[u8], start: usize) -> Result<usize, String> {
+    let mut i = start + 1;
+    while i < b.len() {
+        match b[i] {
+            b'\\' => i += 2,
+            b'"' => return Ok(i + 1),
+            _ => i += 1,
+        }
+    }
+    Err("Unterminated string".to_string())
+}
+
+fn value_end(b: &[u8], start: usize) -> Result<usize, String> {
+    match b.get(start) {
+        Some(b'"') => string_end(b, start),
+        Some(&open @ (b'{' | b'[')) => {
+            let close = if open == b'{' { b'}' } else { b']' };
+            let mut i = skip_trivia(b, start + 1);
+            loop {
+                match b.get(i) {
+                    None => return Err("Unterminated object or array".to_string()),
+                    Some(&c) if c == close => return Ok(i + 1),
+                    Some(b',' | b':') => i = skip_trivia(b, i + 1),
+                    Some(_) => i = skip_trivia(b, value_end(b, i)?),
+                }
+            }
+        }
+        Some(_) => {
+            let end = b[start..]
+                .iter()
+                .position(|c| c.is_ascii_whitespace() || b",:]}/".contains(c))
+                .map_or(b.len(), |len| start + len);
+            if end == start {
+                Err(format!("Unexpected character at byte {start}"))
+            } else {
+                Ok(end)
+            }
+        }
+        None => Err("Unexpected end of file".to_string()),
+    }
+}
+
+/// Members of the object starting at `object`, and the index of its `}`.
+fn members(text: &str, object: usize) -> Result<(Vec<Member>, usize), String> {
+    let b = text.as_bytes();
+    let mut members = Vec::new();
+    let mut i = skip_trivia(b, object + 1);
+    loop {
+        match b.get(i) {
+            Some(b'}') => return Ok((members, i)),
+            Some(b',') => i = skip_trivia(b, i + 1),
+            Some(b'"') => {
+                let key_end = string_end(b, i)?;
+                let key: String =
+                    serde_json::from_str(&text[i..key_end]).map_err(|e| e.to_string())?;
+                let colon = skip_trivia(b, key_end);
+                if b.get(colon) != Some(&b':') {
+                    return Err(format!("Expected ':' after {key:?}"));
+                }
+                let value_start = skip_trivia(b, colon + 1);
+                let value_end = value_end(b, value_start)?;
+                members.push(Member {
+                    key,
+                    key_start: i,
+                    value_start,
+                    value_end,
+                });
+                i = skip_trivia(b, value_end);
+            }
+            _ => return Err(format!("Unexpected character at byte {i}")),
+        }
+    }
+}
+
+/// (start, end) of each element of the array starting at `array`, and the
+/// index of its `]`.
+fn elements(text: &str, array: usize) -> Result<(Vec<(usize, usize)>, usize), String> {
+    let b = text.as_bytes();
+    let mut elements = Vec::new();
+    let mut i = skip_trivia(b, array + 1);
+    loop {
+        match b.get(i) {
+            Some(b']') => return Ok((elements, i)),
+            Some(b',') => i = skip_trivia(b, i + 1),
+            Some(_) => {
+                let end = value_end(b, i)?;
+                elements.push((i, end));
+                i = skip_trivia(b, end);
+            }
+            None => return Err("Unterminated array".to_string()),
+        }
+    }
+}
+
+/// Start of the value at `path`.
+fn locate(text: &str, path: &[Seg]) -> Result<usize, String> {
+    let b = text.as_bytes();
+    let mut at = skip_trivia(b, 0);
+    for seg in path {
+        at = match *seg {
+            Seg::Key(key) => {
+                if b.get(at) != Some(&b'{') {
+                    return Err(format!("Expected an object around {key:?}"));
+                }
+                members(text, at)?
+                    .0
+                    .into_iter()
+                    .find(|m| m.key == key)
+                    .map(|m| m.value_start)
+                    .ok_or_else(|| format!("{key:?} not found"))?
+            }
+            Seg::Index(index) => {
+                if b.get(at) != Some(&b'[') {
+                    return Err(format!("Expected an array around element {index}"));
+                }
+                elements(text, at)?
+                    .0
+                    .get(index)
+                    .map(|&(start, _)| start)
+                    .ok_or_else(|| format!("Element {index} not found"))?
+            }
+        };
+    }
+    if at >= b.len() {
+        return Err("Unexpected end of file".to_string());
+    }
+    Ok(at)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn strips_comments_and_trailing_commas() {
+        let text = "{\n  // leading\n  \"a\": 1, /* inline */\n  \"b\": [1, 2,],\n}";
+        assert_eq!(parse(text).unwrap(), json!({ "a": 1, "b": [1, 2] }));
+    }
+
+    #[test]
+    fn trailing_comma_before_comment() {
+        let text = "{\n  \"a\": 1, // last\n}";
+        assert_eq!(parse(text).unwrap(), json!({ "a": 1 }));
+        let text = "[\n  1, /* last */\n]";
+        assert_eq!(parse(text).unwrap(), json!([1]));
+    }
+
+    #[test]
+    fn keeps_comment_markers_inside_strings() {
+        let text = r#"{ "url": "https://example.com/*x*/", "s": "a, }" }"#;
+        assert_eq!(
+            parse(text).unwrap(),
+            json!({ "url": "https://example.com/*x*/", "s": "a, }" })
+        );
+        let text = r#"{ "q": "say \"//hi\"" }"#;
+        assert_eq!(parse(text).unwrap(), json!({ "q": "say \"//hi\"" }));
+    }
+
+    #[test]
+    fn set_replaces_value_and_keeps_comments() {
+        let text = "{\n    // port of the DAP server\n    \"server\": \"localhost:1\", // old\n    \"chip\": \"RP2040\"\n}";
+        let edited = set(text, &[], "server", &json!("host:2")).unwrap();
+        assert!(edited.contains("// port of the DAP server"));
+        assert!(edited.contains("\"server\": \"host:2\", // old"));
+        assert_eq!(
+            parse(&edited).unwrap(),
+            json!({ "server": "host:2", "chip": "RP2040" })
+        );
+    }
+
+    #[test]
+    fn set_inserts_missing_key() {
+        let text = "{\n    \"configurations\": [\n        {\n            \"type\": \"x\" // keep\n        }\n    ]\n}";
+        let path = [Seg::Key("configurations"), Seg::Index(0)];
+        let edited = set(text, &path, "speed", &json!(4000)).unwrap();
+        assert!(edited.contains("// keep"));
+        assert_eq!(
+            parse(&edited).unwrap(),
+            json!({ "configurations": [{ "speed": 4000, "type": "x" }] })
+        );
+        let edited = set("{}", &[], "runArgs", &json!(["a"])).unwrap();
+        assert_eq!(parse(&edited).unwrap(), json!({ "runArgs": ["a"] }));
+    }
+
+    #[test]
+    fn remove_member() {
+        let text = "{\n    \"a\": 1,\n    \"b\": 2, // b\n    \"c\": 3\n}";
+        for (key, expected) in [
+            ("a", json!({ "b": 2, "c": 3 })),
+            ("b", json!({ "a": 1, "c": 3 })),
+            ("c", json!({ "a": 1, "b": 2 })),
+        ] {
+            assert_eq!(parse(&remove(text, &[], key).unwrap()).unwrap(), expected);
+        }
+        assert_eq!(
+            parse(&remove("{ \"a\": 1, }", &[], "a").unwrap()).unwrap(),
+            json!({})
+        );
+        assert_eq!(remove(text, &[], "missing").unwrap(), text);
+    }
+
+    #[test]
+    fn push_appends_element() {
+        let text = "{\n    // args\n    \"runArgs\": [\n        \"--init\", // first\n    ]\n}";
+        let edited = push(text, &[Seg::Key("runArgs")], &json!("--x")).unwrap();
+        assert!(edited.contains("// first"));
+        assert_eq!(
+            parse(&edited).unwrap(),
+            json!({ "runArgs": ["--init", "--x"] })
+        );
+        let edited = push("{ \"a\": [] }", &[Seg::Key("a")], &json!({ "b": 1 })).unwrap();
+        assert_eq!(parse(&edited).unwrap(), json!({ "a": [{ "b": 1 }] }));
+    }
+}
@@ -80,6 +80,52 @@ impl Default for DisplayBuffer {
 
 // ---- Log line display helpers ----
 
+/// Lowest level shown in the Log panel.
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Info,
+    #[default]
+    Debug,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 3] = [LogLevel::Error, LogLevel::Info, LogLevel::Debug];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LogLevel::Error => "Errors only",
+            LogLevel::Info => "Info and errors",
+            LogLevel::Debug => "Everything, including probe-rs output",
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.id() == id)
+            .unwrap_or_default()
+    }
+
+    /// Whether a buffered line is shown at this level.
+    pub fn shows(self, line: &str) -> bool {
+        match self {
+            LogLevel::Error => line.contains("[ERROR]"),
+            LogLevel::Info => line.contains("[ERROR]") || line.contains("[INFO]"),
+            LogLevel::Debug => true,
+        }
+    }
+}
+
 pub fn log_level_class(line: &str) -> &'static str {
     if line.contains("[ERROR]") {
         "log-error"
@@ -1,11 +1,22 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod app;
+mod cargo_task;
 mod cmd;
+mod docker;
+mod doctor;
+mod editor;
+mod engine;
 mod helpers;
+mod jsonc;
 mod logger;
 mod parameter;
+mod probe_firmware;
+mod project;
 mod settings;
+mod template_cache;
+mod templates;
+mod udev;
 
 use dioxus::desktop::{Config, LogicalSize, WindowBuilder};
 use log::LevelFilter;
@@ -18,7 +29,13 @@ static DAP_SERVER: OnceLock<Mutex<cmd::ProbeRsDapServer>> = OnceLock::new();
 pub use logger::{display_buffer, log_error, log_info};
 
 pub fn dap_server() -> &'static Mutex<cmd::ProbeRsDapServer> {
-    DAP_SERVER.get_or_init(|| Mutex::new(cmd::ProbeRsDapServer::default()))
+    DAP_SERVER.get_or_init(|| {
+        let settings = settings::load();
+        let mut server = cmd::ProbeRsDapServer::default();
+        server.ip = settings.dap_ip;
+        server.port = settings.dap_port.to_string();
+        Mutex::new(server)
+    })
 }
 
 fn main() {
@@ -30,6 +47,11 @@ fn main() {
     }
     let _ = logger.try_init();
 
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("doctor") {
+        std::process::exit(doctor::cli(&args[2..]));
+    }
+
     let icon = helpers::load_window_icon();
     dioxus::LaunchBuilder::desktop()
         .with_cfg(
@@ -1,7 +1,16 @@
 pub const APP_NAME: &str = "Baker link. Env";
 pub const TUTORIAL_TEMPLATE: &str =
     "https://github.com/Baker-link-Lab/bakerlink_tutorial_template.git";
-pub const TEMPLATE_URL: &str = "https://github.com/Baker-link-Lab/bakerlink_tutorial_template";
+pub const RP2040_TEMPLATE: &str = "https://github.com/rp-rs/rp2040-project-template.git";
+pub const EMBASSY_TEMPLATE: &str = "https://github.com/lulf/embassy-template.git";
+pub const RTIC_TEMPLATE: &str = "https://github.com/rtic-rs/defmt-app-template.git";
+pub const DEFAULT_DAP_PORT: u16 = 50001;
+pub const DEFAULT_DAP_IP: &str = "127.0.0.1";
+/// USB ids of the Baker link on-board CMSIS-DAP probe (Raspberry Pi debugprobe).
+pub const BAKER_LINK_VID: u16 = 0x2e8a;
+pub const BAKER_LINK_PID: u16 = 0x000c;
+/// Highest SWD clock of the on-board probe firmware.
+pub const BAKER_LINK_MAX_CLOCK_KHZ: u32 = 25_000;
 
 pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const GIT_HASH: &str = env!("GIT_HASH");
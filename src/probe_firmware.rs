@@ -0,0 +1,330 @@
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use nusb::descriptors::TransferType;
+use nusb::transfer::{Buffer, Bulk, Direction, In, Out};
+use nusb::MaybeFuture as _;
+
+use crate::{cmd, parameter};
+
+const USB_TIMEOUT: Duration = Duration::from_secs(1);
+/// Volume label of the RP2040 USB bootloader (BOOTSEL mode).
+const BOOTLOADER_LABEL: &str = "RPI-RP2";
+const BOOTLOADER_INFO_FILE: &str = "INFO_UF2.TXT";
+const UF2_MAGIC_START0: u32 = 0x0A32_4655;
+const UF2_MAGIC_START1: u32 = 0x9E5D_5157;
+const UF2_FAMILY_ID_PRESENT: u32 = 0x0000_2000;
+const RP2040_FAMILY_ID: u32 = 0xE48B_FF56;
+
+/// DAP_Info IDs from the CMSIS-DAP specification.
+const DAP_INFO: u8 = 0x00;
+const INFO_VENDOR: u8 = 0x01;
+const INFO_PRODUCT: u8 = 0x02;
+const INFO_PROTOCOL_VERSION: u8 = 0x04;
+const INFO_FIRMWARE_VERSION: u8 = 0x09;
+const INFO_CAPABILITIES: u8 = 0xF0;
+const INFO_UART_RX_BUFFER: u8 = 0xFB;
+const INFO_SWO_BUFFER: u8 = 0xFD;
+const INFO_PACKET_SIZE: u8 = 0xFF;
+
+/// What the probe firmware reports about itself, shown for Baker link probes.
+#[derive(Clone, PartialEq)]
+pub struct ProbeFirmware {
+    pub product: String,
+    /// Product firmware version, or the USB device release when the firmware
+    /// predates CMSIS-DAP 2.1.
+    pub version: String,
+    pub protocol_version: String,
+    pub swd: bool,
+    pub jtag: bool,
+    pub swo: bool,
+    /// UART bridge over the DAP commands or a USB serial port.
+    pub uart_bridge: bool,
+    pub max_clock_khz: Option<u32>,
+    pub packet_size: Option<u16>,
+}
+
+impl ProbeFirmware {
+    /// `SWD, SWO, UART bridge`, for the probe info grid.
+    pub fn capabilities(&self) -> String {
+        [
+            (self.swd, "SWD"),
+            (self.jtag, "JTAG"),
+            (self.swo, "SWO"),
+            (self.uart_bridge, "UART bridge"),
+        ]
+        .iter()
+        .filter(|(supported, _)| *supported)
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+pub fn is_baker_link(probe: &cmd::ProbeInfo) -> bool {
+    probe.vendor_id == parameter::BAKER_LINK_VID && probe.product_id == parameter::BAKER_LINK_PID
+}
+
+fn find_device(probe: &cmd::ProbeInfo) -> Result<nusb::DeviceInfo, String> {
+    nusb::list_devices()
+        .wait()
+        .map_err(|e| format!("Listing USB devices failed: {}", e))?
+        .find(|d| {
+            d.vendor_id() == probe.vendor_id
+                && d.product_id() == probe.product_id
+                && (probe.serial_number.is_none()
+                    || d.serial_number() == probe.serial_number.as_deref())
+        })
+        .ok_or_else(|| format!("{} is no longer connected", probe.identifier))
+}
+
+/// Sends DAP_Info requests over the CMSIS-DAP v2 bulk interface. Fails while
+/// the DAP server holds the probe.
+pub fn read(probe: &cmd::ProbeInfo) -> Result<ProbeFirmware, String> {
+    let info = find_device(probe)?;
+    let device = info
+        .open()
+        .wait()
+        .map_err(|e| format!("Opening {} failed: {}", probe.identifier, e))?;
+    let config = device
+        .active_configuration()
+        .map_err(|e| format!("Reading the USB configuration failed: {}", e))?;
+    // debugprobe exposes its UART bridge as a CDC-ACM serial port
+    let has_serial_port = config
+        .interface_alt_settings()
+        .any(|alt| alt.class() == 0x02);
+    let (interface_number, ep_out, ep_in) = config
+        .interface_alt_settings()
+        .filter(|alt| alt.class() == 0xff)
+        .find_map(|alt| {
+            let bulk = |direction| {
+                alt.endpoints()
+                    .find(|ep| {
+                        ep.transfer_type() == TransferType::Bulk && ep.direction() == direction
+                    })
+                    .map(|ep| ep.address())
+            };
+            Some((
+                alt.interface_number(),
+                bulk(Direction::Out)?,
+                bulk(Direction::In)?,
+            ))
+        })
+        .ok_or("The probe has no CMSIS-DAP v2 interface; update its firmware")?;
+    let interface = device
+        .claim_interface(interface_number)
+        .wait()
+        .map_err(|e| {
+            format!(
+                "The probe is in use ({}). Stop the DAP server and try again.",
+                e
+            )
+        })?;
+    let mut dap = DapInterface {
+        ep_out: interface
+            .endpoint::<Bulk, Out>(ep_out)
+            .map_err(|e| e.to_string())?,
+        ep_in: interface
+            .endpoint::<Bulk, In>(ep_in)
+            .map_err(|e| e.to_string())?,
+    };
+
+    let capabilities = dap.info(INFO_CAPABILITIES)?;
+    let flag = |byte: usize, bit: u8| capabilities.get(byte).is_some_and(|b| b & (1 << bit) != 0);
+    let device_release = info.device_version();
+    let product = dap
+        .text(INFO_PRODUCT)
+        .or_else(|| dap.text(INFO_VENDOR))
+        .or_else(|| info.product_string().map(str::to_string))
+        .unwrap_or_else(|| probe.identifier.clone());
+    let version = dap
+        .text(INFO_FIRMWARE_VERSION)
+        .unwrap_or_else(|| format!("{:x}.{:02x}", device_release >> 8, device_release & 0xff));
+    Ok(ProbeFirmware {
+        product,
+        version,
+        protocol_version: dap.text(INFO_PROTOCOL_VERSION).unwrap_or_default(),
+        swd: flag(0, 0),
+        jtag: flag(0, 1),
+        swo: (flag(0, 2) || flag(0, 3)) && dap.number(INFO_SWO_BUFFER).is_some_and(|size| size > 0),
+        uart_bridge: (flag(0, 7) && dap.number(INFO_UART_RX_BUFFER).is_some_and(|size| size > 0))
+            || flag(1, 0)
+            || has_serial_port,
+        // CMSIS-DAP has no query for it; only the on-board probe's limit is known
+        max_clock_khz: is_baker_link(probe).then_some(parameter::BAKER_LINK_MAX_CLOCK_KHZ),
+        packet_size: dap.number(INFO_PACKET_SIZE).map(|size| size as u16),
+    })
+}
+
+/// Bulk endpoints of a claimed CMSIS-DAP v2 interface.
+struct DapInterface {
+    ep_out: nusb::Endpoint<Bulk, Out>,
+    ep_in: nusb::Endpoint<Bulk, In>,
+}
+
+impl DapInterface {
+    fn info(&mut self, id: u8) -> Result<Vec<u8>, String> {
+        self.ep_out
+            .transfer_blocking(Buffer::from(vec![DAP_INFO, id]), USB_TIMEOUT)
+            .status
+            .map_err(|e| format!("DAP_Info request failed: {}", e))?;
+        let mut request = Buffer::new(512);
+        request.set_requested_len(512);
+        let response = self.ep_in.transfer_blocking(request, USB_TIMEOUT);
+        response
+            .status
+            .map_err(|e| format!("DAP_Info response failed: {}", e))?;
+        match &response.buffer[..] {
+            [DAP_INFO, len, data @ ..] if data.len() >= *len as usize => {
+                Ok(data[..*len as usize].to_vec())
+            }
+            _ => Err("Unexpected DAP_Info response".to_string()),
+        }
+    }
+
+    /// String item, `None` when the firmware does not provide it.
+    fn text(&mut self, id: u8) -> Option<String> {
+        let data = self.info(id).ok()?;
+        let text = String::from_utf8_lossy(&data)
+            .trim_end_matches('\0')
+            .trim()
+            .to_string();
+        (!text.is_empty()).then_some(text)
+    }
+
+    fn number(&mut self, id: u8) -> Option<u32> {
+        match self.info(id).ok()?.as_slice() {
+            [a, b] => Some(u16::from_le_bytes([*a, *b]) as u32),
+            [a, b, c, d] => Some(u32::from_le_bytes([*a, *b, *c, *d])),
+            _ => None,
+        }
+    }
+}
+
+/// Mount point of the RP2040 bootloader drive, found by its `INFO_UF2.TXT`.
+pub fn find_bootloader_drive() -> Option<PathBuf> {
+    let mut candidates = Vec::new();
+    if cfg!(target_os = "windows") {
+        candidates
+            .extend((b'D'..=b'Z').map(|letter| PathBuf::from(format!("{}:\\", letter as char))));
+    } else if cfg!(target_os = "macos") {
+        candidates.push(Path::new("/Volumes").join(BOOTLOADER_LABEL));
+    } else {
+        let user = std::env::var("USER").unwrap_or_default();
+        for root in ["/media", "/run/media"] {
+            candidates.push(Path::new(root).join(&user).join(BOOTLOADER_LABEL));
+        }
+        candidates.push(Path::new("/media").join(BOOTLOADER_LABEL));
+    }
+    candidates.into_iter().find(|dir| {
+        std::fs::read_to_string(dir.join(BOOTLOADER_INFO_FILE))
+            .is_ok_and(|info| info.contains(BOOTLOADER_LABEL))
+    })
+}
+
+/// Checks that `file` is a UF2 image for the RP2040 and returns its block count.
+pub fn validate_uf2(file: &Path) -> Result<u32, String> {
+    let mut data = Vec::new();
+    std::fs::File::open(file)
+        .and_then(|mut f| f.read_to_end(&mut data))
+        .map_err(|e| format!("{}: {}", file.display(), e))?;
+    if data.is_empty() || data.len() % 512 != 0 {
+        return Err(format!("{} is not a UF2 file", file.display()));
+    }
+    let word = |block: &[u8], offset: usize| {
+        u32::from_le_bytes([
+            block[offset],
+            block[offset + 1],
+            block[offset + 2],
+            block[offset + 3],
+        ])
+    };
+    for block in data.chunks(512) {
+        if word(block, 0) != UF2_MAGIC_START0 || word(block, 4) != UF2_MAGIC_START1 {
+            return Err(format!("{} is not a UF2 file", file.display()));
+        }
+        if word(block, 8) & UF2_FAMILY_ID_PRESENT != 0 && word(block, 28) != RP2040_FAMILY_ID {
+            return Err(format!("{} is not built for the RP2040", file.display()));
+        }
+    }
+    Ok((data.len() / 512) as u32)
+}
+
+/// Copies the firmware onto the bootloader drive. The probe reboots into the
+/// new firmware once the copy is complete.
+pub fn flash_uf2(file: &Path, drive: &Path) -> Result<(), String> {
+    let name = file.file_name().ok_or("Invalid firmware file name")?;
+    let dest = drive.join(name);
+    let mut source = std::fs::File::open(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+    let mut target =
+        std::fs::File::create(&dest).map_err(|e| format!("{}: {}", dest.display(), e))?;
+    std::io::copy(&mut source, &mut target).map_err(|e| format!("{}: {}", dest.display(), e))?;
+    // The drive disappears as soon as the last block is written
+    let _ = target.sync_all();
+    Ok(())
+}
+
+/// Polls every 500 ms until `found` returns a value or `timeout` elapses.
+pub fn wait_for<T>(timeout: Duration, mut found: impl FnMut() -> Option<T>) -> Option<T> {
+    let started = Instant::now();
+    loop {
+        if let Some(value) = found() {
+            return Some(value);
+        }
+        if started.elapsed() >= timeout {
+            return None;
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
+/// The Baker link probe once it is back on USB after an update.
+pub fn find_baker_link() -> Option<cmd::ProbeInfo> {
+    cmd::list_probes().into_iter().find(is_baker_link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(family: Option<u32>) -> Vec<u8> {
+        let mut block = vec![0; 512];
+        block[0..4].copy_from_slice(&UF2_MAGIC_START0.to_le_bytes());
+        block[4..8].copy_from_slice(&UF2_MAGIC_START1.to_le_bytes());
+        if let Some(family) = family {
+            block[8..12].copy_from_slice(&UF2_FAMILY_ID_PRESENT.to_le_bytes());
+            block[28..32].copy_from_slice(&family.to_le_bytes());
+        }
+        block
+    }
+
+    fn validate(data: &[u8]) -> Result<u32, String> {
+        let file = std::env::temp_dir().join(format!(
+            "baker-link-env-uf2-{}-{}.uf2",
+            std::process::id(),
+            data.len()
+        ));
+        std::fs::write(&file, data).unwrap();
+        let result = validate_uf2(&file);
+        let _ = std::fs::remove_file(&file);
+        result
+    }
+
+    #[test]
+    fn accepts_rp2040_images() {
+        let image = [block(Some(RP2040_FAMILY_ID)), block(None)].concat();
+        assert_eq!(validate(&image), Ok(2));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(validate(b"").is_err());
+        assert!(validate(&[0; 512]).is_err());
+        assert!(validate(&block(None)[..500]).is_err());
+        let other_family = block(Some(0x68ed_2b88));
+        assert!(validate(&other_family)
+            .unwrap_err()
+            .contains("not built for the RP2040"));
+    }
+}
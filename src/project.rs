@@ -0,0 +1,886 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde_json::{json, Map, Value};
+
+use crate::jsonc::{self, Seg};
+use crate::{docker, settings};
+
+pub const LAUNCH_JSON: &str = ".vscode/launch.json";
+pub const DEVCONTAINER_JSON: &str = ".devcontainer/devcontainer.json";
+const CARGO_TOML: &str = "Cargo.toml";
+const CARGO_CONFIG: &str = ".cargo/config.toml";
+
+pub const DEBUG_TYPE: &str = "probe-rs-debug";
+pub const DOCKER_HOST: &str = "host.docker.internal";
+const LAUNCH_NAME: &str = "probe-rs (Baker link. Env)";
+const DEFAULT_TARGET: &str = "thumbv6m-none-eabi";
+const WORKSPACE_FOLDER: &str = "${workspaceFolder}";
+
+/// Values written into the probe-rs launch configuration.
+pub struct LaunchParams {
+    pub port: u16,
+    pub chip: Option<String>,
+    pub probe: Option<String>,
+    pub speed_khz: Option<u32>,
+    pub connect_under_reset: bool,
+}
+
+impl LaunchParams {
+    /// Replaces the global values with the project's own ones where set.
+    pub fn with_overrides(self, overrides: &settings::ProjectOverrides) -> Self {
+        Self {
+            port: overrides.dap_port.unwrap_or(self.port),
+            chip: overrides.chip.clone().or(self.chip),
+            probe: overrides.probe.clone().or(self.probe),
+            speed_khz: overrides.speed_khz.or(self.speed_khz),
+            connect_under_reset: overrides.connect_under_reset || self.connect_under_reset,
+        }
+    }
+}
+
+/// Writes `.vscode/launch.json`, or patches the first `probe-rs-debug`
+/// configuration of an existing one, so the Dev Container talks to the
+/// DAP server on the host.
+pub fn write_launch_json(project: &Path, params: &LaunchParams) -> anyhow::Result<PathBuf> {
+    update_launch_config(project, |config| {
+        patch_launch_config(config, project, params)
+    })
+}
+
+/// Applies `edit` to the first `probe-rs-debug` configuration in
+/// `launch.json`, creating the file or the configuration when missing.
+/// An existing file is edited in place, so the user's comments are kept.
+fn update_launch_config(
+    project: &Path,
+    edit: impl FnOnce(&mut Map<String, Value>),
+) -> anyhow::Result<PathBuf> {
+    let path = project.join(LAUNCH_JSON);
+    if !path.exists() {
+        let mut config = new_launch_config(project);
+        if let Some(config) = config.as_object_mut() {
+            edit(config);
+        }
+        write_json(
+            &path,
+            &json!({ "version": "0.2.0", "configurations": [config] }),
+        )?;
+        return Ok(path);
+    }
+
+    let mut text = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let jsonc_error = |e: String| anyhow::anyhow!("{}: {}", path.display(), e);
+    let root = jsonc::parse(&text).map_err(jsonc_error)?;
+    if !root.is_object() {
+        anyhow::bail!("launch.json is not a JSON object");
+    }
+    let configs = match root.get("configurations") {
+        Some(Value::Array(configs)) => configs.clone(),
+        Some(_) => anyhow::bail!("launch.json `configurations` is not an array"),
+        None => {
+            text = jsonc::set(&text, &[], "configurations", &json!([])).map_err(jsonc_error)?;
+            Vec::new()
+        }
+    };
+
+    let (index, before) = match configs.iter().position(is_probe_rs_config) {
+        Some(index) => (index, configs[index].clone()),
+        None => {
+            let config = new_launch_config(project);
+            text =
+                jsonc::push(&text, &[Seg::Key("configurations")], &config).map_err(jsonc_error)?;
+            (configs.len(), config)
+        }
+    };
+    let before = before
+        .as_object()
+        .context("probe-rs configuration is not a JSON object")?;
+    let mut after = before.clone();
+    edit(&mut after);
+
+    let at = [Seg::Key("configurations"), Seg::Index(index)];
+    for (key, value) in &after {
+        if before.get(key) != Some(value) {
+            text = jsonc::set(&text, &at, key, value).map_err(jsonc_error)?;
+        }
+    }
+    for key in before.keys().filter(|key| !after.contains_key(*key)) {
+        text = jsonc::remove(&text, &at, key).map_err(jsonc_error)?;
+    }
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
+
+fn write_json(path: &Path, value: &Value) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+pub fn is_probe_rs_config(config: &Value) -> bool {
+    config.get("type").and_then(Value::as_str) == Some(DEBUG_TYPE)
+}
+
+fn new_launch_config(project: &Path) -> Value {
+    json!({
+        "type": DEBUG_TYPE,
+        "request": "launch",
+        "name": LAUNCH_NAME,
+        "cwd": WORKSPACE_FOLDER,
+        "flashingConfig": { "flashingEnabled": true },
+        "coreConfigs": [
+            { "coreIndex": 0, "programBinary": program_binary(project) }
+        ],
+    })
+}
+
+fn patch_launch_config(config: &mut Map<String, Value>, project: &Path, params: &LaunchParams) {
+    config.insert("server".into(), json!(dap_server_address(params.port)));
+    if let Some(chip) = &params.chip {
+        config.insert("chip".into(), json!(chip));
+    }
+    if let Some(probe) = &params.probe {
+        config.insert("probe".into(), json!(probe));
+    }
+    if let Some(speed) = params.speed_khz {
+        config.insert("speed".into(), json!(speed));
+    }
+    if params.connect_under_reset {
+        config.insert("connectUnderReset".into(), json!(true));
+    } else {
+        config.remove("connectUnderReset");
+    }
+    config.insert("pathMappings".into(), path_mappings(project));
+}
+
+pub fn dap_server_address(port: u16) -> String {
+    format!("{DOCKER_HOST}:{port}")
+}
+
+/// Maps the container workspace (what VS Code sends) onto the host path
+/// the DAP server sees.
+pub fn path_mappings(project: &Path) -> Value {
+    json!([{
+        "localRoot": WORKSPACE_FOLDER,
+        "remoteRoot": host_path(project),
+    }])
+}
+
+pub fn host_path(project: &Path) -> String {
+    project.to_string_lossy().to_string()
+}
+
+/// ELF path relative to the workspace, as produced by `cargo build`.
+pub fn program_binary(project: &Path) -> String {
+    let package = package_name(project).unwrap_or_else(|| folder_name(project));
+    format!(
+        "{WORKSPACE_FOLDER}/target/{}/debug/{package}",
+        build_target(project)
+    )
+}
+
+pub fn folder_name(project: &Path) -> String {
+    project
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// `package.name` from the project's `Cargo.toml`.
+pub fn package_name(project: &Path) -> Option<String> {
+    let manifest = read_toml(&project.join(CARGO_TOML))?;
+    manifest
+        .get("package")?
+        .get("name")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// `build.target` from `.cargo/config.toml`, falling back to the RP2040 triple.
+pub fn build_target(project: &Path) -> String {
+    read_toml(&project.join(CARGO_CONFIG))
+        .and_then(|config| {
+            config
+                .get("build")?
+                .get("target")?
+                .as_str()
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| DEFAULT_TARGET.to_string())
+}
+
+fn read_toml(path: &Path) -> Option<toml::Value> {
+    let text = std::fs::read_to_string(path).ok()?;
+    toml::from_str(&text).ok()
+}
+
+/// Reads a VS Code style JSON file, which may contain comments and
+/// trailing commas.
+pub fn read_jsonc(path: &Path) -> anyhow::Result<Value> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    jsonc::parse(&text).map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// One problem found by [`check_project`], with an optional automatic fix.
+#[derive(Clone, PartialEq)]
+pub struct CheckIssue {
+    pub message: String,
+    pub fix: Option<CheckFix>,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum CheckFix {
+    CreateLaunchJson,
+    Server(String),
+    Chip(String),
+    PathMappings,
+    ProgramBinary(String),
+    AddHostGateway,
+}
+
+impl CheckFix {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CheckFix::CreateLaunchJson => "Create",
+            CheckFix::Server(_) => "Set server",
+            CheckFix::Chip(_) => "Set chip",
+            CheckFix::PathMappings => "Fix mappings",
+            CheckFix::ProgramBinary(_) => "Fix ELF path",
+            CheckFix::AddHostGateway => "Add host",
+        }
+    }
+}
+
+/// What the running app knows about the debug setup.
+pub struct CheckContext {
+    pub port: u16,
+    pub chip: Option<String>,
+}
+
+/// Compares the project's `launch.json` and `devcontainer.json` with the
+/// DAP server, the detected chip and the project's location on disk.
+pub fn check_project(project: &Path, ctx: &CheckContext) -> Vec<CheckIssue> {
+    let mut issues = Vec::new();
+    let has_devcontainer = check_devcontainer(project, &mut issues);
+
+    let launch_path = project.join(LAUNCH_JSON);
+    if !launch_path.exists() {
+        issues.push(CheckIssue {
+            message: format!("{LAUNCH_JSON} not found"),
+            fix: Some(CheckFix::CreateLaunchJson),
+        });
+        return issues;
+    }
+    let launch = match read_jsonc(&launch_path) {
+        Ok(launch) => launch,
+        Err(e) => {
+            issues.push(CheckIssue {
+                message: format!("{e:#}"),
+                fix: None,
+            });
+            return issues;
+        }
+    };
+    let config = launch
+        .get("configurations")
+        .and_then(Value::as_array)
+        .and_then(|configs| configs.iter().find(|c| is_probe_rs_config(c)));
+    let Some(config) = config else {
+        issues.push(CheckIssue {
+            message: format!("No `{DEBUG_TYPE}` configuration in {LAUNCH_JSON}"),
+            fix: Some(CheckFix::CreateLaunchJson),
+        });
+        return issues;
+    };
+
+    check_server(config, ctx.port, has_devcontainer, &mut issues);
+    check_chip(config, ctx.chip.as_deref(), &mut issues);
+    check_path_mappings(config, project, &mut issues);
+    check_program_binary(config, project, &mut issues);
+    issues
+}
+
+fn check_devcontainer(project: &Path, issues: &mut Vec<CheckIssue>) -> bool {
+    let path = project.join(DEVCONTAINER_JSON);
+    if !path.exists() {
+        return false;
+    }
+    match read_jsonc(&path) {
+        Ok(devcontainer) => {
+            // Docker Desktop and Rancher Desktop provide host.docker.internal;
+            // a plain Linux dockerd needs it mapped to the host gateway.
+            if cfg!(target_os = "linux") && !has_host_gateway(&devcontainer) {
+                issues.push(CheckIssue {
+                    message: format!("{DEVCONTAINER_JSON} does not map {DOCKER_HOST} to the host"),
+                    fix: Some(CheckFix::AddHostGateway),
+                });
+            }
+        }
+        Err(e) => issues.push(CheckIssue {
+            message: format!("{e:#}"),
+            fix: None,
+        }),
+    }
+    true
+}
+
+fn has_host_gateway(devcontainer: &Value) -> bool {
+    devcontainer
+        .get("runArgs")
+        .and_then(Value::as_array)
+        .is_some_and(|args| {
+            args.iter()
+                .filter_map(Value::as_str)
+                .any(|arg| arg.contains(DOCKER_HOST))
+        })
+}
+
+fn check_server(config: &Value, port: u16, has_devcontainer: bool, issues: &mut Vec<CheckIssue>) {
+    let expected = dap_server_address(port);
+    let Some(server) = config.get("server").and_then(Value::as_str) else {
+        issues.push(CheckIssue {
+            message: "`server` is not set; the debugger will not use the host DAP server"
+                .to_string(),
+            fix: Some(CheckFix::Server(expected)),
+        });
+        return;
+    };
+    let (host, server_port) = server.rsplit_once(':').unwrap_or((server, ""));
+    if server_port.parse::<u16>().ok() != Some(port) {
+        issues.push(CheckIssue {
+            message: format!("`server` uses port {server_port:?}, the DAP server uses {port}"),
+            fix: Some(CheckFix::Server(expected)),
+        });
+    } else if has_devcontainer && host != DOCKER_HOST {
+        issues.push(CheckIssue {
+            message: format!("`server` host {host:?} is not reachable from the Dev Container"),
+            fix: Some(CheckFix::Server(expected)),
+        });
+    }
+}
+
+fn check_chip(config: &Value, detected: Option<&str>, issues: &mut Vec<CheckIssue>) {
+    let chip = config.get("chip").and_then(Value::as_str);
+    match (chip, detected) {
+        (None, _) => issues.push(CheckIssue {
+            message: "`chip` is not set".to_string(),
+            fix: detected.map(|d| CheckFix::Chip(d.to_string())),
+        }),
+        (Some(chip), Some(detected)) if !chip.eq_ignore_ascii_case(detected) => {
+            issues.push(CheckIssue {
+                message: format!("`chip` is {chip:?}, but {detected:?} is connected"),
+                fix: Some(CheckFix::Chip(detected.to_string())),
+            })
+        }
+        _ => {}
+    }
+}
+
+fn check_path_mappings(config: &Value, project: &Path, issues: &mut Vec<CheckIssue>) {
+    let mapped = config
+        .get("pathMappings")
+        .and_then(Value::as_array)
+        .is_some_and(|mappings| {
+            mappings.iter().any(|m| {
+                m.get("remoteRoot")
+                    .and_then(Value::as_str)
+                    .is_some_and(|root| Path::new(root) == project)
+            })
+        });
+    if !mapped {
+        issues.push(CheckIssue {
+            message: format!(
+                "`pathMappings` has no remoteRoot for {}",
+                host_path(project)
+            ),
+            fix: Some(CheckFix::PathMappings),
+        });
+    }
+}
+
+fn check_program_binary(config: &Value, project: &Path, issues: &mut Vec<CheckIssue>) {
+    let expected = program_binary(project);
+    let binaries = config
+        .get("coreConfigs")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|core| core.get("programBinary").and_then(Value::as_str));
+    for binary in binaries {
+        if normalize_binary(binary) != normalize_binary(&expected) {
+            issues.push(CheckIssue {
+                message: format!("`programBinary` is {binary:?}, Cargo.toml builds {expected:?}"),
+                fix: Some(CheckFix::ProgramBinary(expected.clone())),
+            });
+        }
+    }
+}
+
+fn normalize_binary(path: &str) -> &str {
+    path.trim_start_matches(WORKSPACE_FOLDER)
+        .trim_start_matches(['/', '\\'])
+        .trim_start_matches("./")
+}
+
+/// Applies one fix reported by [`check_project`].
+pub fn apply_fix(project: &Path, fix: &CheckFix, params: &LaunchParams) -> anyhow::Result<()> {
+    match fix {
+        CheckFix::CreateLaunchJson => {
+            write_launch_json(project, params)?;
+        }
+        CheckFix::Server(server) => {
+            update_launch_config(project, |config| {
+                config.insert("server".into(), json!(server));
+            })?;
+        }
+        CheckFix::Chip(chip) => {
+            update_launch_config(project, |config| {
+                config.insert("chip".into(), json!(chip));
+            })?;
+        }
+        CheckFix::PathMappings => {
+            update_launch_config(project, |config| {
+                config.insert("pathMappings".into(), path_mappings(project));
+            })?;
+        }
+        CheckFix::ProgramBinary(binary) => {
+            update_launch_config(project, |config| {
+                let cores = config
+                    .entry("coreConfigs")
+                    .or_insert_with(|| json!([{ "coreIndex": 0 }]));
+                if let Some(cores) = cores.as_array_mut() {
+                    for core in cores.iter_mut().filter_map(Value::as_object_mut) {
+                        core.insert("programBinary".into(), json!(binary));
+                    }
+                }
+            })?;
+        }
+        CheckFix::AddHostGateway => {
+            let path = project.join(DEVCONTAINER_JSON);
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let jsonc_error = |e: String| anyhow::anyhow!("{}: {}", path.display(), e);
+            let devcontainer = jsonc::parse(&text).map_err(jsonc_error)?;
+            let arg = json!(format!("--add-host={DOCKER_HOST}:host-gateway"));
+            let text = match devcontainer
+                .as_object()
+                .context("devcontainer.json is not a JSON object")?
+                .get("runArgs")
+            {
+                Some(Value::Array(_)) => jsonc::push(&text, &[Seg::Key("runArgs")], &arg),
+                Some(_) => anyhow::bail!("devcontainer.json `runArgs` is not an array"),
+                None => jsonc::set(&text, &[], "runArgs", &json!([arg])),
+            }
+            .map_err(jsonc_error)?;
+            std::fs::write(&path, text)?;
+        }
+    }
+    Ok(())
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Names cargo refuses because they clash with the standard library or build layout.
+const RESERVED_NAMES: &[&str] = &[
+    "alloc",
+    "core",
+    "proc_macro",
+    "std",
+    "test",
+    "build",
+    "deps",
+    "examples",
+    "incremental",
+];
+
+/// Checks `name` against cargo's package name rules.
+pub fn validate_crate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Project name is required".to_string());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_'))
+    {
+        return Err(format!(
+            "{c:?} is not allowed; use letters, digits, '-' or '_'"
+        ));
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err("Project name must not start with a digit".to_string());
+    }
+    if RUST_KEYWORDS.contains(&name) {
+        return Err(format!("{name:?} is a Rust keyword"));
+    }
+    if RESERVED_NAMES.contains(&name.replace('-', "_").as_str()) {
+        return Err(format!("{name:?} is reserved by cargo"));
+    }
+    Ok(())
+}
+
+/// Turns arbitrary input into a name that passes [`validate_crate_name`].
+pub fn suggest_crate_name(name: &str) -> String {
+    let mut suggestion = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            suggestion.push(c.to_ascii_lowercase());
+        } else if !suggestion.ends_with('-') {
+            suggestion.push('-');
+        }
+    }
+    let mut suggestion = suggestion.trim_matches('-').to_string();
+    if suggestion.is_empty() {
+        return "myproject".to_string();
+    }
+    if suggestion.starts_with(|c: char| c.is_ascii_digit()) {
+        suggestion.insert_str(0, "project-");
+    }
+    if validate_crate_name(&suggestion).is_err() {
+        suggestion.push_str("-project");
+    }
+    suggestion
+}
+
+/// Problems with the folder a project is about to be generated into.
+pub fn validate_destination(dir: &Path) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err(format!("{} is not a folder", dir.display()));
+    }
+    // Rancher Desktop / WSL bind mounts mangle non-ASCII Windows paths
+    if cfg!(target_os = "windows") && !dir.to_string_lossy().is_ascii() {
+        return Err(format!(
+            "{} contains non-ASCII characters, which break Docker mounts on Windows",
+            dir.display()
+        ));
+    }
+    let probe = dir.join(format!(".baker-link-env-write-test-{}", std::process::id()));
+    std::fs::write(&probe, b"").map_err(|e| format!("{} is not writable: {e}", dir.display()))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Builds a history entry for a Cargo project that was not created by the app.
+pub fn import_project(dir: &Path) -> Result<settings::HistoryEntry, String> {
+    if !dir.join(CARGO_TOML).is_file() {
+        return Err(format!("{} has no {CARGO_TOML}", dir.display()));
+    }
+    Ok(settings::HistoryEntry {
+        name: package_name(dir).unwrap_or_else(|| folder_name(dir)),
+        path: host_path(dir),
+        devcontainer: has_devcontainer(dir),
+        ..Default::default()
+    })
+}
+
+pub fn has_devcontainer(project: &Path) -> bool {
+    project.join(DEVCONTAINER_JSON).is_file() || project.join(".devcontainer.json").is_file()
+}
+
+/// `vscode-remote://dev-container+<hex host path><workspace>` URI that opens the
+/// project straight inside its Dev Container, or `None` without
+/// `.devcontainer/devcontainer.json`.
+pub fn dev_container_uri(project: &Path) -> Option<String> {
+    let config = read_jsonc(&project.join(DEVCONTAINER_JSON)).ok()?;
+    let workspace = workspace_folder(project, &config);
+    let hex: String = host_path(project)
+        .bytes()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    Some(format!("vscode-remote://dev-container+{hex}{workspace}"))
+}
+
+/// Where the project is mounted inside its Dev Container.
+fn workspace_folder(project: &Path, config: &Value) -> String {
+    let folder = folder_name(project);
+    config
+        .get("workspaceFolder")
+        .and_then(Value::as_str)
+        .map(|w| w.replace("${localWorkspaceFolderBasename}", &folder))
+        .unwrap_or_else(|| format!("/workspaces/{folder}"))
+}
+
+/// Workspace folder and `remoteUser` used to run commands in the project's
+/// Dev Container.
+pub fn container_exec_target(project: &Path) -> (String, Option<String>) {
+    let config = read_jsonc(&project.join(DEVCONTAINER_JSON)).unwrap_or(Value::Null);
+    let user = ["remoteUser", "containerUser"]
+        .iter()
+        .find_map(|key| config.get(*key).and_then(Value::as_str))
+        .map(str::to_string);
+    (workspace_folder(project, &config), user)
+}
+
+/// State of a project's Dev Container shown in History.
+#[derive(Clone, Copy, PartialEq)]
+pub enum DevContainerState {
+    Running,
+    Stopped,
+    /// The Dev Container config changed after the container was created.
+    Stale,
+    /// The project has a Dev Container config but no container yet.
+    Missing,
+}
+
+impl DevContainerState {
+    pub fn label(self) -> &'static str {
+        match self {
+            DevContainerState::Running => "Running",
+            DevContainerState::Stopped => "Stopped",
+            DevContainerState::Stale => "Stale",
+            DevContainerState::Missing => "Not created",
+        }
+    }
+}
+
+/// Compares folders the way Docker Desktop / WSL report them: case and
+/// separators are ignored on Windows.
+fn same_folder(label: &str, project: &Path) -> bool {
+    let project = host_path(project);
+    if cfg!(target_os = "windows") {
+        let normalize = |p: &str| p.replace('/', "\\").trim_end_matches('\\').to_lowercase();
+        normalize(label) == normalize(&project)
+    } else {
+        label.trim_end_matches('/') == project.trim_end_matches('/')
+    }
+}
+
+/// The project's Dev Container, preferring a running one when VS Code left
+/// several behind.
+pub fn find_dev_container<'a>(
+    project: &Path,
+    containers: &'a [docker::DevContainer],
+) -> Option<&'a docker::DevContainer> {
+    containers
+        .iter()
+        .filter(|c| same_folder(&c.local_folder, project))
+        .max_by_key(|c| (c.is_running(), c.created))
+}
+
+/// `None` for projects without a Dev Container config and no container.
+pub fn dev_container_state(
+    project: &Path,
+    container: Option<&docker::DevContainer>,
+) -> Option<DevContainerState> {
+    let Some(container) = container else {
+        return has_devcontainer(project).then_some(DevContainerState::Missing);
+    };
+    let config_changed = [
+        DEVCONTAINER_JSON,
+        ".devcontainer/Dockerfile",
+        ".devcontainer.json",
+    ]
+    .iter()
+    .filter_map(|file| std::fs::metadata(project.join(file)).ok()?.modified().ok())
+    .filter_map(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+    .any(|modified| modified.as_secs() as i64 > container.created);
+    Some(if config_changed {
+        DevContainerState::Stale
+    } else if container.is_running() {
+        DevContainerState::Running
+    } else {
+        DevContainerState::Stopped
+    })
+}
+
+/// Whether `image` was built by the Dev Containers tooling for `project`.
+/// Those are named `vsc-<folder>-<hash>`, with `-uid` or `-features` added
+/// at times; base images shared with other projects never match.
+pub fn is_project_built_image(project: &Path, image: &str) -> bool {
+    let Some(folder) = project.file_name() else {
+        return false;
+    };
+    // The tooling drops everything but these characters from the folder name
+    let folder: String = folder
+        .to_string_lossy()
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+        .collect();
+    let name = image.rsplit('/').next().unwrap_or(image);
+    let name = name.split(':').next().unwrap_or(name);
+    name.strip_prefix("vsc-")
+        .and_then(|rest| rest.strip_prefix(folder.as_str()))
+        .is_some_and(|rest| rest.starts_with('-'))
+}
+
+/// Whether pruning must keep the image tagged `tag`: one of `images`, which
+/// are normalized with [`docker::normalize_image`], or an image built for
+/// one of `projects`.
+pub fn is_needed_image(tag: &str, images: &[String], projects: &[PathBuf]) -> bool {
+    images.contains(&docker::normalize_image(tag))
+        || projects
+            .iter()
+            .any(|project| is_project_built_image(project, tag))
+}
+
+/// Images a Dev Container config needs: its `image`, or the `FROM` images of
+/// its Dockerfile. The second value is true when the config builds an image.
+pub fn dev_container_images(dir: &Path) -> Result<(Vec<String>, bool), String> {
+    let config_path = [DEVCONTAINER_JSON, ".devcontainer.json"]
+        .iter()
+        .map(|file| dir.join(file))
+        .find(|path| path.is_file())
+        .ok_or_else(|| format!("No Dev Container config in {}", dir.display()))?;
+    let config = read_jsonc(&config_path).map_err(|e| format!("{:#}", e))?;
+    if let Some(image) = config.get("image").and_then(Value::as_str) {
+        return Ok((vec![image.to_string()], false));
+    }
+    let build = config.get("build").unwrap_or(&config);
+    let Some(dockerfile) = build
+        .get("dockerfile")
+        .or_else(|| build.get("dockerFile"))
+        .and_then(Value::as_str)
+    else {
+        return Err(format!(
+            "{} has no image or Dockerfile (Docker Compose is not supported)",
+            config_path.display()
+        ));
+    };
+    let config_dir = config_path.parent().unwrap_or(dir);
+    let text = std::fs::read_to_string(config_dir.join(dockerfile))
+        .map_err(|e| format!("Failed to read {}: {}", dockerfile, e))?;
+    Ok((dockerfile_base_images(&text), true))
+}
+
+/// External images in `FROM` lines, skipping earlier build stages and images
+/// that depend on build arguments.
+fn dockerfile_base_images(dockerfile: &str) -> Vec<String> {
+    let mut stages: Vec<String> = Vec::new();
+    let mut images = Vec::new();
+    for line in dockerfile.lines() {
+        let mut words = line.split_whitespace();
+        if !words.next().is_some_and(|w| w.eq_ignore_ascii_case("FROM")) {
+            continue;
+        }
+        let mut words = words.skip_while(|w| w.starts_with("--"));
+        let Some(image) = words.next() else {
+            continue;
+        };
+        if !image.contains('$')
+            && !image.eq_ignore_ascii_case("scratch")
+            && !stages.iter().any(|s| s.eq_ignore_ascii_case(image))
+            && !images.iter().any(|i| i == image)
+        {
+            images.push(image.to_string());
+        }
+        if words.next().is_some_and(|w| w.eq_ignore_ascii_case("AS")) {
+            if let Some(stage) = words.next() {
+                stages.push(stage.to_string());
+            }
+        }
+    }
+    images
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dev_container_uri_uses_workspace_folder() {
+        let project =
+            std::env::temp_dir().join(format!("baker-link-env-uri-{}", std::process::id()));
+        std::fs::create_dir_all(project.join(".devcontainer")).unwrap();
+        std::fs::write(
+            project.join(DEVCONTAINER_JSON),
+            "{\n    // mounted elsewhere\n    \"workspaceFolder\": \"/work/${localWorkspaceFolderBasename}\",\n}",
+        )
+        .unwrap();
+        let uri = dev_container_uri(&project);
+        let _ = std::fs::remove_dir_all(&project);
+
+        let hex: String = host_path(&project)
+            .bytes()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        let folder = folder_name(&project);
+        assert_eq!(
+            uri.as_deref(),
+            Some(format!("vscode-remote://dev-container+{hex}/work/{folder}").as_str())
+        );
+        assert_eq!(dev_container_uri(&project), None);
+    }
+
+    #[test]
+    fn crate_names() {
+        for name in ["blinky", "my-app", "my_app_2", "Rp2040"] {
+            assert!(validate_crate_name(name).is_ok(), "{name}");
+        }
+        for name in [
+            "",
+            "2fast",
+            "my app",
+            "a.b",
+            "fn",
+            "self",
+            "std",
+            "proc-macro",
+        ] {
+            assert!(validate_crate_name(name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn built_images_belong_to_their_project() {
+        let project = Path::new("/home/me/My Blinky");
+        assert!(is_project_built_image(project, "vsc-myblinky-0123abcd"));
+        assert!(is_project_built_image(
+            project,
+            "vsc-myblinky-0123abcd-uid:latest"
+        ));
+        assert!(!is_project_built_image(project, "vsc-myblinky2-0123abcd"));
+        assert!(!is_project_built_image(
+            project,
+            "mcr.microsoft.com/devcontainers/rust:1"
+        ));
+    }
+
+    #[test]
+    fn prune_keeps_history_images() {
+        let images = vec!["rust:1.80".to_string()];
+        let projects = vec![PathBuf::from("/home/me/My Blinky")];
+        assert!(is_needed_image(
+            "docker.io/library/rust:1.80",
+            &images,
+            &projects
+        ));
+        assert!(is_needed_image(
+            "vsc-myblinky-0123abcd-uid:latest",
+            &images,
+            &projects
+        ));
+        assert!(!is_needed_image(
+            "vsc-my-blinky-0123abcd",
+            &images,
+            &projects
+        ));
+        assert!(!is_needed_image("rust:1.79", &images, &projects));
+    }
+
+    #[test]
+    fn dockerfile_images_skip_stages_and_arguments() {
+        let dockerfile = "\
+ARG BASE=rust
+FROM --platform=linux/amd64 rust:1.80 AS build
+FROM build AS test
+FROM ${BASE}
+from debian:bookworm-slim
+FROM scratch
+FROM rust:1.80
+";
+        assert_eq!(
+            dockerfile_base_images(dockerfile),
+            ["rust:1.80", "debian:bookworm-slim"]
+        );
+    }
+}
@@ -1,50 +1,407 @@
-use std::path::PathBuf;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value};
+
+use crate::editor::Editor;
+use crate::engine::EngineKind;
+use crate::logger::LogLevel;
+use crate::parameter;
+use crate::templates::TemplateEntry;
 
 const APP_DIR_NAME: &str = "baker-link-env";
 const SETTINGS_FILE: &str = "settings.json";
+const BACKUP_FILE: &str = "settings.json.bak";
+const BROKEN_FILE: &str = "settings.json.broken";
+const TEMP_FILE: &str = "settings.json.tmp";
+const VERSION_KEY: &str = "version";
+const HISTORY_KEY: &str = "history";
+/// Overrides the system-wide preset location, e.g. for a shared network drive.
+const PRESET_ENV: &str = "BAKER_LINK_ENV_PRESET";
 
-pub const HISTORY_MAX: usize = 10;
+static PRESET: OnceLock<Map<String, Value>> = OnceLock::new();
 
-#[derive(Clone, serde::Serialize, serde::Deserialize)]
+/// `MIGRATIONS[i]` upgrades a settings file from schema `i + 1` to `i + 2`.
+/// Files without a `version` field are schema 1.
+const MIGRATIONS: &[fn(&mut Value)] = &[migrate_v1_history_order];
+pub const SETTINGS_VERSION: u64 = MIGRATIONS.len() as u64 + 1;
+
+pub const DEFAULT_HISTORY_MAX: usize = 10;
+/// Sortable local time used for `HistoryEntry::last_opened`.
+const HISTORY_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct HistoryEntry {
     pub name: String,
     pub path: String,
+    #[serde(default)]
+    pub devcontainer: bool,
+    #[serde(default)]
+    pub last_opened: String,
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub template_ref: Option<String>,
+    #[serde(default)]
+    pub chip: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub overrides: ProjectOverrides,
+}
+
+/// Per-project values that replace the global DAP and probe settings
+/// when the project is opened from History.
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ProjectOverrides {
+    #[serde(default)]
+    pub dap_port: Option<u16>,
+    /// probe-rs probe selector, `VID:PID[:serial]`.
+    #[serde(default)]
+    pub probe: Option<String>,
+    #[serde(default)]
+    pub chip: Option<String>,
+    #[serde(default)]
+    pub speed_khz: Option<u32>,
+    #[serde(default)]
+    pub connect_under_reset: bool,
+}
+
+impl ProjectOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl HistoryEntry {
+    /// Case-insensitive match on name, path, template and chip.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || [
+                Some(&self.name),
+                Some(&self.path),
+                self.template.as_ref(),
+                self.chip.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(&query))
+    }
+
+    /// One-line metadata shown under the path in the History dropdown.
+    pub fn summary(&self) -> String {
+        let template = self.template.as_ref().map(|t| match &self.template_ref {
+            Some(r) => format!("{t}@{r}"),
+            None => t.clone(),
+        });
+        let opened = (!self.last_opened.is_empty()).then(|| {
+            let minutes = self.last_opened.get(..16).unwrap_or(&self.last_opened);
+            format!("opened {minutes}")
+        });
+        [template, self.chip.clone(), opened]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" \u{00b7} ")
+    }
 }
 
-#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct AppSettings {
     #[serde(default)]
     pub history: Vec<HistoryEntry>,
+    #[serde(default = "default_history_max")]
+    pub history_max: usize,
     #[serde(default)]
     pub last_splash_date: String,
+    #[serde(default)]
+    pub templates: Vec<TemplateEntry>,
+    #[serde(default)]
+    pub selected_template: String,
+    #[serde(default)]
+    pub offline_mode: bool,
+    #[serde(default = "default_dap_ip")]
+    pub dap_ip: String,
+    #[serde(default = "default_dap_port")]
+    pub dap_port: u16,
+    /// Probe selector written to launch.json when a project has no override.
+    #[serde(default)]
+    pub probe: Option<String>,
+    #[serde(default)]
+    pub editor: Editor,
+    /// Command for `Editor::Custom`, e.g. `subl {path}`.
+    #[serde(default)]
+    pub editor_command: String,
+    /// Open projects with a Dev Container config directly inside the container.
+    #[serde(default)]
+    pub open_in_container: bool,
+    #[serde(default)]
+    pub log_level: LogLevel,
+    #[serde(default = "default_true")]
+    pub show_splash: bool,
+    #[serde(default)]
+    pub container_engine: EngineKind,
+    /// Pull or build the Dev Container images of history projects when
+    /// Docker comes up.
+    #[serde(default = "default_true")]
+    pub prepare_images: bool,
 }
 
-fn settings_path() -> PathBuf {
+/// A group of settings with its own reset in the Settings view.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SettingsSection {
+    Dap,
+    Templates,
+    Editor,
+    Log,
+    History,
+    Splash,
+    Engine,
+}
+
+impl SettingsSection {
+    /// `AppSettings` fields that belong to the section.
+    fn keys(self) -> &'static [&'static str] {
+        match self {
+            SettingsSection::Dap => &["dap_ip", "dap_port", "probe"],
+            SettingsSection::Templates => &["templates", "selected_template", "offline_mode"],
+            SettingsSection::Editor => &["editor", "editor_command", "open_in_container"],
+            SettingsSection::Log => &["log_level"],
+            SettingsSection::History => &["history_max"],
+            SettingsSection::Splash => &["show_splash", "last_splash_date"],
+            SettingsSection::Engine => &["container_engine", "prepare_images"],
+        }
+    }
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            history: Vec::new(),
+            history_max: DEFAULT_HISTORY_MAX,
+            last_splash_date: String::new(),
+            templates: Vec::new(),
+            selected_template: String::new(),
+            offline_mode: false,
+            dap_ip: default_dap_ip(),
+            dap_port: default_dap_port(),
+            probe: None,
+            editor: Editor::default(),
+            editor_command: String::new(),
+            open_in_container: false,
+            log_level: LogLevel::default(),
+            show_splash: true,
+            container_engine: EngineKind::default(),
+            prepare_images: true,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_history_max() -> usize {
+    DEFAULT_HISTORY_MAX
+}
+
+fn default_dap_ip() -> String {
+    parameter::DEFAULT_DAP_IP.to_string()
+}
+
+fn default_dap_port() -> u16 {
+    parameter::DEFAULT_DAP_PORT
+}
+
+pub fn config_dir() -> PathBuf {
     dirs::config_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join(APP_DIR_NAME)
-        .join(SETTINGS_FILE)
 }
 
+fn settings_path() -> PathBuf {
+    config_dir().join(SETTINGS_FILE)
+}
+
+/// Loads the settings. A file that cannot be read or parsed is reported to the
+/// Log panel, moved aside as `settings.json.broken`, and replaced by the backup
+/// of the previous save so history is not lost.
 pub fn load() -> AppSettings {
-    std::fs::read_to_string(settings_path())
-        .ok()
-        .and_then(|data| serde_json::from_str(&data).ok())
-        .unwrap_or_default()
+    let path = settings_path();
+    let error = match read_settings(&path) {
+        Ok(Some(settings)) => return settings,
+        Ok(None) => return AppSettings::default(),
+        Err(e) => e,
+    };
+    crate::log_error(format!("Failed to load {}: {error}", path.display()));
+    let broken = config_dir().join(BROKEN_FILE);
+    if std::fs::rename(&path, &broken).is_ok() {
+        crate::log_info(format!("Unreadable settings kept as {}", broken.display()));
+    }
+    match read_settings(&config_dir().join(BACKUP_FILE)) {
+        Ok(Some(settings)) => {
+            crate::log_info("Settings restored from the previous backup");
+            save(&settings);
+            settings
+        }
+        _ => AppSettings::default(),
+    }
 }
 
+/// Saves the settings, reporting failures to the Log panel.
 pub fn save(settings: &AppSettings) {
-    let path = settings_path();
-    if let Some(parent) = path.parent() {
-        let _ = std::fs::create_dir_all(parent);
+    if let Err(e) = write_settings(settings) {
+        crate::log_error(format!("Failed to save settings: {e}"));
     }
-    if let Ok(data) = serde_json::to_string_pretty(settings) {
-        let _ = std::fs::write(&path, data);
+}
+
+/// Reads a settings file with the system-wide preset merged under it.
+/// Without a user file the preset alone is used.
+fn read_settings(path: &Path) -> Result<Option<AppSettings>, String> {
+    let user = read_settings_map(path)?;
+    let preset = preset();
+    if user.is_none() && preset.is_empty() {
+        return Ok(None);
     }
+    let mut merged = preset.clone();
+    merged.extend(user.unwrap_or_default());
+    serde_json::from_value(Value::Object(merged))
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+/// Top-level keys of a settings file after migration, without `version`.
+fn read_settings_map(path: &Path) -> Result<Option<Map<String, Value>>, String> {
+    let data = match std::fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let mut value: Value = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    migrate(&mut value);
+    let Value::Object(mut map) = value else {
+        return Err("expected a JSON object".to_string());
+    };
+    map.remove(VERSION_KEY);
+    Ok(Some(map))
+}
+
+/// System-wide defaults set by an administrator, read once per run.
+fn preset() -> &'static Map<String, Value> {
+    PRESET.get_or_init(|| {
+        let Some(path) = preset_path() else {
+            return Map::new();
+        };
+        let preset = read_settings_map(&path).and_then(|map| match map {
+            // Check the preset on its own so a bad value is never blamed on the user file
+            Some(map) => serde_json::from_value::<AppSettings>(Value::Object(map.clone()))
+                .map(|_| Some(map))
+                .map_err(|e| e.to_string()),
+            None => Ok(None),
+        });
+        match preset {
+            Ok(Some(map)) => {
+                crate::log_info(format!("Settings preset loaded: {}", path.display()));
+                map
+            }
+            Ok(None) => Map::new(),
+            Err(e) => {
+                crate::log_error(format!("Ignoring preset {}: {e}", path.display()));
+                Map::new()
+            }
+        }
+    })
+}
+
+pub fn preset_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(PRESET_ENV) {
+        return Some(PathBuf::from(path));
+    }
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var_os("ProgramData")
+            .map(|dir| PathBuf::from(dir).join(APP_DIR_NAME).join(SETTINGS_FILE))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        Some(
+            PathBuf::from("/Library/Application Support")
+                .join(APP_DIR_NAME)
+                .join(SETTINGS_FILE),
+        )
+    }
+    #[cfg(target_os = "linux")]
+    {
+        Some(PathBuf::from("/etc").join(APP_DIR_NAME).join(SETTINGS_FILE))
+    }
+}
+
+fn to_map(settings: &AppSettings) -> Result<Map<String, Value>, String> {
+    match serde_json::to_value(settings).map_err(|e| e.to_string())? {
+        Value::Object(map) => Ok(map),
+        _ => Err("settings did not serialize to an object".to_string()),
+    }
+}
+
+fn to_versioned_json(mut map: Map<String, Value>) -> Result<String, String> {
+    map.insert(VERSION_KEY.into(), SETTINGS_VERSION.into());
+    serde_json::to_string_pretty(&Value::Object(map)).map_err(|e| e.to_string())
+}
+
+/// Brings an older settings file up to [`SETTINGS_VERSION`]. Files written by
+/// a newer app are read as-is; unknown fields are ignored.
+fn migrate(value: &mut Value) {
+    let version = value.get(VERSION_KEY).and_then(Value::as_u64).unwrap_or(1);
+    for migration in MIGRATIONS.iter().skip(version.saturating_sub(1) as usize) {
+        migration(value);
+    }
+}
+
+/// Schema 1 kept history oldest-first without `last_opened`; reverse it so the
+/// recency sort keeps the most recent project on top.
+fn migrate_v1_history_order(value: &mut Value) {
+    if let Some(history) = value.get_mut("history").and_then(Value::as_array_mut) {
+        history.reverse();
+    }
+}
+
+/// Writes `settings.json.tmp`, backs up the current file, then renames the
+/// temporary file over it so a crash never leaves a half-written file.
+fn write_settings(settings: &AppSettings) -> Result<(), String> {
+    let dir = config_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+
+    // Only values that differ from the preset are stored, so later preset
+    // changes still reach settings the user has not touched
+    let mut map = to_map(settings)?;
+    let preset = preset();
+    map.retain(|key, value| preset.get(key) != Some(value));
+    write_atomic(&to_versioned_json(map)?)
+}
+
+fn write_atomic(data: &str) -> Result<(), String> {
+    let dir = config_dir();
+    let temp = dir.join(TEMP_FILE);
+    let mut file = std::fs::File::create(&temp).map_err(|e| format!("{}: {e}", temp.display()))?;
+    file.write_all(data.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("{}: {e}", temp.display()))?;
+    drop(file);
+
+    let path = settings_path();
+    if path.exists() {
+        std::fs::copy(&path, dir.join(BACKUP_FILE))
+            .map_err(|e| format!("Failed to back up {}: {e}", path.display()))?;
+    }
+    std::fs::rename(&temp, &path).map_err(|e| format!("{}: {e}", path.display()))
 }
 
 pub fn load_history() -> Vec<HistoryEntry> {
-    load().history
+    let mut history = load().history;
+    sort_history(&mut history);
+    history
 }
 
 pub fn save_history(entries: &[HistoryEntry]) {
@@ -53,9 +410,210 @@ pub fn save_history(entries: &[HistoryEntry]) {
     save(&s);
 }
 
+pub fn history_timestamp() -> String {
+    chrono::Local::now().format(HISTORY_TIME_FORMAT).to_string()
+}
+
+/// Pinned entries first, then the most recently opened.
+fn sort_history(entries: &mut [HistoryEntry]) {
+    entries.sort_by(|a, b| {
+        b.pinned
+            .cmp(&a.pinned)
+            .then_with(|| b.last_opened.cmp(&a.last_opened))
+    });
+}
+
+/// Sorts `entries` and drops the least recently opened unpinned ones above `max`.
+fn trim_history(entries: &mut Vec<HistoryEntry>, max: usize) {
+    sort_history(entries);
+    while entries.len() > max {
+        let Some(pos) = entries.iter().rposition(|e| !e.pinned) else {
+            break;
+        };
+        let removed = entries.remove(pos);
+        crate::log_info(format!(
+            "History is full ({} entries): removed {}",
+            max, removed.name
+        ));
+    }
+}
+
+/// Adds or refreshes `entry` (matched by path) as the most recently opened one.
+/// Pin state and metadata the new entry lacks are kept from the old one.
+pub fn add_history(mut entry: HistoryEntry) -> Vec<HistoryEntry> {
+    let mut s = load();
+    if let Some(pos) = s.history.iter().position(|e| e.path == entry.path) {
+        let old = s.history.remove(pos);
+        entry.pinned |= old.pinned;
+        entry.template = entry.template.or(old.template);
+        entry.template_ref = entry.template_ref.or(old.template_ref);
+        entry.chip = entry.chip.or(old.chip);
+        // Overrides are edited through `update_history`; re-adding keeps them
+        if entry.overrides.is_empty() {
+            entry.overrides = old.overrides;
+        }
+    }
+    entry.last_opened = history_timestamp();
+    s.history.push(entry);
+    trim_history(&mut s.history, s.history_max);
+    save(&s);
+    s.history
+}
+
+/// Applies `update` to the entry for `path` and returns the re-sorted history.
+pub fn update_history(path: &str, update: impl FnOnce(&mut HistoryEntry)) -> Vec<HistoryEntry> {
+    let mut s = load();
+    if let Some(entry) = s.history.iter_mut().find(|e| e.path == path) {
+        update(entry);
+    }
+    sort_history(&mut s.history);
+    save(&s);
+    s.history
+}
+
+pub fn load_overrides(path: &str) -> ProjectOverrides {
+    load()
+        .history
+        .into_iter()
+        .find(|e| e.path == path)
+        .map(|e| e.overrides)
+        .unwrap_or_default()
+}
+
+pub fn load_history_max() -> usize {
+    load().history_max
+}
+
+pub fn save_history_max(max: usize) -> Vec<HistoryEntry> {
+    let mut s = load();
+    s.history_max = max.max(1);
+    trim_history(&mut s.history, s.history_max);
+    save(&s);
+    s.history
+}
+
+pub fn save_templates(templates: &[TemplateEntry]) {
+    let mut s = load();
+    s.templates = templates.to_vec();
+    save(&s);
+}
+
+pub fn load_selected_template() -> String {
+    load().selected_template
+}
+
+pub fn save_selected_template(name: &str) {
+    let mut s = load();
+    s.selected_template = name.to_string();
+    save(&s);
+}
+
+pub fn load_offline_mode() -> bool {
+    load().offline_mode
+}
+
+pub fn save_offline_mode(offline: bool) {
+    let mut s = load();
+    s.offline_mode = offline;
+    save(&s);
+}
+
+pub fn save_dap(ip: &str, port: u16) {
+    let mut s = load();
+    s.dap_ip = ip.to_string();
+    s.dap_port = port;
+    save(&s);
+}
+
+/// Writes the effective settings (preset included) to `dest`, optionally
+/// leaving out the machine-specific project history.
+pub fn export_settings(dest: &Path, include_history: bool) -> Result<(), String> {
+    let mut map = to_map(&load())?;
+    if !include_history {
+        map.remove(HISTORY_KEY);
+    }
+    std::fs::write(dest, to_versioned_json(map)?)
+        .map_err(|e| format!("Failed to write {}: {e}", dest.display()))
+}
+
+/// Replaces the settings present in `src`. Settings the file does not contain,
+/// such as history in an export made without it, keep their current value.
+pub fn import_settings(src: &Path) -> Result<AppSettings, String> {
+    let imported = read_settings_map(src)
+        .map_err(|e| format!("{}: {e}", src.display()))?
+        .ok_or_else(|| format!("{} not found", src.display()))?;
+    let mut map = to_map(&load())?;
+    map.extend(imported);
+    let settings: AppSettings = serde_json::from_value(Value::Object(map))
+        .map_err(|e| format!("{}: {e}", src.display()))?;
+    write_settings(&settings)?;
+    Ok(settings)
+}
+
 pub fn should_show_splash() -> bool {
     let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-    load().last_splash_date != today
+    let s = load();
+    s.show_splash && s.last_splash_date != today
+}
+
+pub fn save_show_splash(show: bool) {
+    let mut s = load();
+    s.show_splash = show;
+    save(&s);
+}
+
+pub fn save_probe(probe: Option<String>) {
+    let mut s = load();
+    s.probe = probe;
+    save(&s);
+}
+
+pub fn save_editor(editor: Editor, command: &str) {
+    let mut s = load();
+    s.editor = editor;
+    s.editor_command = command.to_string();
+    save(&s);
+}
+
+pub fn save_open_in_container(enabled: bool) {
+    let mut s = load();
+    s.open_in_container = enabled;
+    save(&s);
+}
+
+pub fn save_log_level(level: LogLevel) {
+    let mut s = load();
+    s.log_level = level;
+    save(&s);
+}
+
+pub fn save_container_engine(engine: EngineKind) {
+    let mut s = load();
+    s.container_engine = engine;
+    save(&s);
+}
+
+pub fn save_prepare_images(enabled: bool) {
+    let mut s = load();
+    s.prepare_images = enabled;
+    save(&s);
+}
+
+/// Drops the section's values from the user file so the preset or the
+/// built-in defaults apply again.
+pub fn reset_section(section: SettingsSection) {
+    let result = read_settings_map(&settings_path()).and_then(|map| {
+        let Some(mut map) = map else {
+            return Ok(());
+        };
+        for key in section.keys() {
+            map.remove(*key);
+        }
+        write_atomic(&to_versioned_json(map)?)
+    });
+    if let Err(e) = result {
+        crate::log_error(format!("Failed to reset settings: {e}"));
+    }
 }
 
 pub fn mark_splash_shown() {
@@ -68,3 +626,26 @@ pub fn reset_all() {
     let path = settings_path();
     let _ = std::fs::remove_file(path);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn migrate_reverses_v1_history() {
+        let mut value = json!({ "history": [{ "path": "old" }, { "path": "new" }] });
+        migrate(&mut value);
+        assert_eq!(value["history"][0]["path"], "new");
+        assert_eq!(value["history"][1]["path"], "old");
+    }
+
+    #[test]
+    fn migrate_keeps_current_files() {
+        let mut value = json!({ "history": [{ "path": "a" }, { "path": "b" }] });
+        value[VERSION_KEY] = SETTINGS_VERSION.into();
+        let before = value.clone();
+        migrate(&mut value);
+        assert_eq!(value, before);
+    }
+}
@@ -0,0 +1,345 @@
+use std::path::{Component, Path, PathBuf};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::templates::{TemplateEntry, TemplateSource};
+use crate::{cmd, settings};
+
+const CACHE_DIR_NAME: &str = "templates";
+const MANIFEST_FILE: &str = "cache.json";
+/// Archive looked up next to the executable to seed an empty cache.
+const BUNDLED_ARCHIVE: &str = "templates.tar.gz";
+
+/// A template copied into the local cache, with the revision it came from.
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CachedTemplate {
+    pub name: String,
+    pub source: String,
+    #[serde(default)]
+    pub git_ref: Option<String>,
+    pub commit: String,
+    pub cached_at: String,
+    pub dir: String,
+}
+
+impl CachedTemplate {
+    pub fn revision_label(&self) -> String {
+        let short = &self.commit[..self.commit.len().min(8)];
+        match &self.git_ref {
+            Some(git_ref) => format!("{git_ref} ({short})"),
+            None => short.to_string(),
+        }
+    }
+
+    /// Warning to show when this copy is used in place of `requested`, a
+    /// branch or tag other than the one it was cached from.
+    pub fn ref_mismatch(&self, requested: Option<&str>) -> Option<String> {
+        match requested {
+            Some(requested) if self.git_ref.as_deref() != Some(requested) => Some(format!(
+                "Using cached {} {} instead of the requested {}",
+                self.name,
+                self.revision_label(),
+                requested
+            )),
+            _ => None,
+        }
+    }
+}
+
+pub fn cache_dir() -> PathBuf {
+    settings::config_dir().join(CACHE_DIR_NAME)
+}
+
+pub fn load_manifest() -> Vec<CachedTemplate> {
+    std::fs::read_to_string(cache_dir().join(MANIFEST_FILE))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_manifest(entries: &[CachedTemplate]) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+    std::fs::write(cache_dir().join(MANIFEST_FILE), data)
+        .map_err(|e| format!("Failed to write template cache manifest: {e}"))
+}
+
+/// Cached copy of `template`, if one exists on disk.
+pub fn find_cached(template: &TemplateEntry) -> Option<(CachedTemplate, PathBuf)> {
+    let entry = load_manifest()
+        .into_iter()
+        .find(|c| c.name == template.name && is_cache_dir_name(&c.dir))?;
+    let dir = cache_dir().join(&entry.dir);
+    dir.is_dir().then_some((entry, dir))
+}
+
+/// Clones `template` into the cache, replacing any previous copy.
+pub fn cache_template(template: &TemplateEntry) -> Result<CachedTemplate, String> {
+    let TemplateSource::Git(url) = &template.source else {
+        return Err(format!(
+            "{} is a local template and is always available offline",
+            template.name
+        ));
+    };
+    let root = cache_dir();
+    std::fs::create_dir_all(&root)
+        .map_err(|e| format!("Failed to create {}: {e}", root.display()))?;
+
+    let mut manifest = load_manifest();
+    let dir_name = cache_dir_for(&template.name, &manifest);
+    let staging = root.join(format!("{dir_name}.tmp"));
+    let _ = std::fs::remove_dir_all(&staging);
+    let git_ref = template.git_ref_name();
+    cmd::clone_template(url, git_ref, &staging, &CancellationToken::new())?;
+    let commit = cmd::git_head_commit(&staging)?;
+    // cargo-generate does not need the history, and it doubles the cache size
+    let _ = std::fs::remove_dir_all(staging.join(".git"));
+
+    let dest = root.join(&dir_name);
+    let _ = std::fs::remove_dir_all(&dest);
+    std::fs::rename(&staging, &dest).map_err(|e| format!("Failed to store template: {e}"))?;
+
+    let entry = CachedTemplate {
+        name: template.name.clone(),
+        source: url.clone(),
+        git_ref: git_ref.map(str::to_string),
+        commit,
+        cached_at: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        dir: dir_name,
+    };
+    manifest.retain(|c| c.name != entry.name);
+    manifest.push(entry.clone());
+    save_manifest(&manifest)?;
+    Ok(entry)
+}
+
+/// Extracts a `.tar.gz` produced by [`export_archive`] into the cache and
+/// merges its manifest. Returns the number of templates imported.
+pub fn import_archive(archive: &Path) -> Result<usize, String> {
+    let root = cache_dir();
+    let staging = root.join("import.tmp");
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create {}: {e}", staging.display()))?;
+
+    let result = unpack_archive(archive, &staging).and_then(|()| {
+        let imported: Vec<CachedTemplate> = std::fs::read_to_string(staging.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .ok_or_else(|| format!("{} has no {MANIFEST_FILE}", archive.display()))?;
+        if let Some(entry) = imported
+            .iter()
+            .find(|c| c.name.is_empty() || !is_cache_dir_name(&c.dir))
+        {
+            return Err(format!(
+                "{} has an invalid entry for \"{}\" ({})",
+                archive.display(),
+                entry.name,
+                entry.dir
+            ));
+        }
+        Ok(imported)
+    });
+    let imported = match result {
+        Ok(imported) => imported,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    let mut manifest = load_manifest();
+    for entry in &imported {
+        // The folder name is ours to choose, whatever the archive says
+        let dir_name = cache_dir_for(&entry.name, &manifest);
+        let dest = root.join(&dir_name);
+        let _ = std::fs::remove_dir_all(&dest);
+        std::fs::rename(staging.join(&entry.dir), &dest)
+            .map_err(|e| format!("Failed to import {}: {e}", entry.name))?;
+        manifest.retain(|c| c.name != entry.name);
+        manifest.push(CachedTemplate {
+            dir: dir_name,
+            ..entry.clone()
+        });
+    }
+    let _ = std::fs::remove_dir_all(&staging);
+    save_manifest(&manifest)?;
+    Ok(imported.len())
+}
+
+/// Extracts `archive` into `staging`, refusing entries that are links or
+/// whose path would land outside `staging`.
+fn unpack_archive(archive: &Path, staging: &Path) -> Result<(), String> {
+    let extract_error = |e: std::io::Error| format!("Failed to extract {}: {e}", archive.display());
+    let file = std::fs::File::open(archive)
+        .map_err(|e| format!("Failed to open {}: {e}", archive.display()))?;
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    for entry in tar.entries().map_err(extract_error)? {
+        let mut entry = entry.map_err(extract_error)?;
+        let path = entry.path().map_err(extract_error)?.into_owned();
+        let entry_type = entry.header().entry_type();
+        let inside = path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+        if !inside || entry_type.is_symlink() || entry_type.is_hard_link() {
+            return Err(format!(
+                "{} contains an unsafe entry: {}",
+                archive.display(),
+                path.display()
+            ));
+        }
+        entry.unpack_in(staging).map_err(extract_error)?;
+    }
+    Ok(())
+}
+
+/// Whether `dir` names a single folder directly inside the cache, so a
+/// tampered manifest cannot point anywhere else.
+fn is_cache_dir_name(dir: &str) -> bool {
+    let mut components = Path::new(dir).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    )
+}
+
+/// Packs the whole cache into a `.tar.gz` that other machines can import.
+pub fn export_archive(dest: &Path) -> Result<(), String> {
+    let file = std::fs::File::create(dest)
+        .map_err(|e| format!("Failed to create {}: {e}", dest.display()))?;
+    let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+        file,
+        flate2::Compression::default(),
+    ));
+    let root = cache_dir();
+    builder
+        .append_path_with_name(root.join(MANIFEST_FILE), MANIFEST_FILE)
+        .map_err(|e| format!("Template cache is empty: {e}"))?;
+    for entry in load_manifest() {
+        builder
+            .append_dir_all(&entry.dir, root.join(&entry.dir))
+            .map_err(|e| format!("Failed to add {}: {e}", entry.name))?;
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(|e| format!("Failed to write {}: {e}", dest.display()))?;
+    Ok(())
+}
+
+/// Imports the archive shipped next to the executable when the cache is empty.
+pub fn seed_from_bundle() -> Result<usize, String> {
+    if !load_manifest().is_empty() {
+        return Ok(0);
+    }
+    let bundled = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(BUNDLED_ARCHIVE)));
+    match bundled {
+        Some(archive) if archive.exists() => {
+            std::fs::create_dir_all(cache_dir()).map_err(|e| e.to_string())?;
+            import_archive(&archive)
+        }
+        _ => Ok(0),
+    }
+}
+
+/// Folder for the template `name`: the one it already has in `manifest`, or
+/// its slug with a number added when another template uses that folder.
+fn cache_dir_for(name: &str, manifest: &[CachedTemplate]) -> String {
+    if let Some(entry) = manifest
+        .iter()
+        .find(|c| c.name == name && is_cache_dir_name(&c.dir))
+    {
+        return entry.dir.clone();
+    }
+    let base = slug(name);
+    let mut dir = base.clone();
+    let mut n = 2;
+    while manifest.iter().any(|c| c.dir == dir) {
+        dir = format!("{base}-{n}");
+        n += 1;
+    }
+    dir
+}
+
+fn slug(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached(git_ref: Option<&str>) -> CachedTemplate {
+        CachedTemplate {
+            name: "Tutorial".to_string(),
+            source: "https://example.com/t.git".to_string(),
+            git_ref: git_ref.map(str::to_string),
+            commit: "0123456789abcdef".to_string(),
+            cached_at: "2024-01-01 00:00".to_string(),
+            dir: "tutorial".to_string(),
+        }
+    }
+
+    #[test]
+    fn manifest_dirs_stay_in_the_cache() {
+        assert!(is_cache_dir_name("tutorial"));
+        assert!(is_cache_dir_name("rp2040-bare-metal"));
+        for dir in ["", ".", "..", "../x", "a/b", "/etc", "a/.."] {
+            assert!(!is_cache_dir_name(dir), "{dir}");
+        }
+    }
+
+    #[test]
+    fn manifest_round_trips() {
+        let entries = vec![cached(Some("v1")), cached(None)];
+        let json = serde_json::to_string(&entries).unwrap();
+        let read: Vec<CachedTemplate> = serde_json::from_str(&json).unwrap();
+        assert!(read == entries);
+        // Manifests written before refs were recorded have no `git_ref`
+        let old: CachedTemplate = serde_json::from_str(
+            r#"{"name":"T","source":"s","commit":"c","cached_at":"t","dir":"t"}"#,
+        )
+        .unwrap();
+        assert_eq!(old.git_ref, None);
+    }
+
+    #[test]
+    fn ref_mismatch_names_both_refs() {
+        assert_eq!(cached(Some("main")).ref_mismatch(Some("main")), None);
+        assert_eq!(cached(Some("main")).ref_mismatch(None), None);
+        let warning = cached(Some("main")).ref_mismatch(Some("v2")).unwrap();
+        assert!(warning.contains("main (01234567)") && warning.contains("v2"));
+        assert!(cached(None).ref_mismatch(Some("v2")).is_some());
+    }
+
+    #[test]
+    fn slug_is_a_plain_folder_name() {
+        assert_eq!(slug("Baker link tutorial"), "baker-link-tutorial");
+        assert_eq!(slug("../Up"), "---up");
+        assert!(is_cache_dir_name(&slug("a/b\\c")));
+    }
+
+    #[test]
+    fn names_with_the_same_slug_get_their_own_folder() {
+        let entry = |name: &str, dir: &str| CachedTemplate {
+            name: name.to_string(),
+            dir: dir.to_string(),
+            ..cached(None)
+        };
+        let mut manifest = vec![entry("RTIC app", "rtic-app")];
+        assert_eq!(cache_dir_for("RTIC app", &manifest), "rtic-app");
+        assert_eq!(cache_dir_for("RTIC-app", &manifest), "rtic-app-2");
+        manifest.push(entry("RTIC-app", "rtic-app-2"));
+        assert_eq!(cache_dir_for("rtic app", &manifest), "rtic-app-3");
+        assert_eq!(cache_dir_for("RTIC-app", &manifest), "rtic-app-2");
+    }
+}
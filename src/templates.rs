@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::{cmd, parameter, settings, template_cache};
+
+const CARGO_GENERATE_TOML: &str = "cargo-generate.toml";
+
+/// Where cargo-generate fetches a template from.
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateSource {
+    Git(String),
+    Path(String),
+}
+
+/// Branch or tag of a Git template.
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateRef {
+    Branch(String),
+    Tag(String),
+}
+
+impl TemplateRef {
+    pub fn name(&self) -> &str {
+        match self {
+            TemplateRef::Branch(name) | TemplateRef::Tag(name) => name,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TemplateEntry {
+    pub name: String,
+    pub source: TemplateSource,
+    #[serde(default)]
+    pub default_ref: Option<TemplateRef>,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl TemplateEntry {
+    /// Link shown under the Create Project card.
+    pub fn web_url(&self) -> Option<String> {
+        match &self.source {
+            TemplateSource::Git(url) if url.starts_with("https://") => {
+                Some(url.trim_end_matches(".git").to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Default branch or tag name, as passed to `git clone --branch`.
+    pub fn git_ref_name(&self) -> Option<&str> {
+        self.default_ref.as_ref().map(TemplateRef::name)
+    }
+
+    pub fn location(&self) -> &str {
+        match &self.source {
+            TemplateSource::Git(url) => url,
+            TemplateSource::Path(path) => path,
+        }
+    }
+}
+
+pub fn builtin_templates() -> Vec<TemplateEntry> {
+    vec![
+        TemplateEntry {
+            name: "Baker link tutorial".to_string(),
+            source: TemplateSource::Git(parameter::TUTORIAL_TEMPLATE.to_string()),
+            default_ref: None,
+            description: "Tutorial project for Baker link. boards with a ready-made Dev Container."
+                .to_string(),
+        },
+        TemplateEntry {
+            name: "RP2040 bare-metal".to_string(),
+            source: TemplateSource::Git(parameter::RP2040_TEMPLATE.to_string()),
+            default_ref: None,
+            description: "Minimal rp2040-hal project from the rp-rs project template.".to_string(),
+        },
+        TemplateEntry {
+            name: "Embassy".to_string(),
+            source: TemplateSource::Git(parameter::EMBASSY_TEMPLATE.to_string()),
+            default_ref: None,
+            description: "Async Embassy project; pick rp2040 as the chip for Baker link. boards."
+                .to_string(),
+        },
+        TemplateEntry {
+            name: "RTIC".to_string(),
+            source: TemplateSource::Git(parameter::RTIC_TEMPLATE.to_string()),
+            default_ref: None,
+            description: "RTIC application with defmt logging from the rtic-rs app template."
+                .to_string(),
+        },
+    ]
+}
+
+/// Built-in templates followed by the user's own entries.
+pub fn catalog() -> Vec<TemplateEntry> {
+    let mut templates = builtin_templates();
+    templates.extend(settings::load().templates);
+    templates
+}
+
+pub fn is_builtin(name: &str) -> bool {
+    builtin_templates().iter().any(|t| t.name == name)
+}
+
+/// Looks up a template by name, falling back to the first entry.
+pub fn find(templates: &[TemplateEntry], name: &str) -> TemplateEntry {
+    templates
+        .iter()
+        .find(|t| t.name == name)
+        .unwrap_or(&templates[0])
+        .clone()
+}
+
+/// Treats URLs and `git@` remotes as Git templates, anything else as a local folder.
+pub fn source_from_input(location: &str) -> TemplateSource {
+    if location.contains("://") || location.starts_with("git@") {
+        TemplateSource::Git(location.to_string())
+    } else {
+        TemplateSource::Path(location.to_string())
+    }
+}
+
+pub fn add_user_template(entry: TemplateEntry) -> Result<(), String> {
+    if entry.name.is_empty() || entry.location().is_empty() {
+        return Err("Template name and location are required".to_string());
+    }
+    if catalog().iter().any(|t| t.name == entry.name) {
+        return Err(format!("Template {} already exists", entry.name));
+    }
+    let mut user_templates = settings::load().templates;
+    user_templates.push(entry);
+    settings::save_templates(&user_templates);
+    Ok(())
+}
+
+pub fn remove_user_template(name: &str) {
+    let mut user_templates = settings::load().templates;
+    user_templates.retain(|t| t.name != name);
+    settings::save_templates(&user_templates);
+}
+
+/// A `[placeholders.<name>]` entry from `cargo-generate.toml`.
+#[derive(Clone, PartialEq)]
+pub struct Placeholder {
+    pub name: String,
+    pub prompt: String,
+    pub kind: PlaceholderKind,
+    pub default: String,
+    pub regex: Option<String>,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum PlaceholderKind {
+    Text,
+    Bool,
+    Choice(Vec<String>),
+}
+
+impl Placeholder {
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        match &self.kind {
+            PlaceholderKind::Bool => match value {
+                "true" | "false" => Ok(()),
+                _ => Err(format!("{}: expected true or false", self.name)),
+            },
+            PlaceholderKind::Choice(choices) => {
+                if choices.iter().any(|c| c == value) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "{}: choose one of {}",
+                        self.name,
+                        choices.join(", ")
+                    ))
+                }
+            }
+            PlaceholderKind::Text => {
+                if value.is_empty() {
+                    return Err(format!("{}: value is required", self.name));
+                }
+                let Some(pattern) = &self.regex else {
+                    return Ok(());
+                };
+                let regex = regex::Regex::new(pattern)
+                    .map_err(|e| format!("{}: invalid regex in template: {e}", self.name))?;
+                if regex.is_match(value) {
+                    Ok(())
+                } else {
+                    Err(format!("{}: must match {pattern}", self.name))
+                }
+            }
+        }
+    }
+}
+
+/// Placeholders read from Git templates, by URL and branch or tag, so
+/// selecting a template again does not clone it again.
+static PLACEHOLDERS: OnceLock<Mutex<HashMap<(String, Option<String>), Vec<Placeholder>>>> =
+    OnceLock::new();
+
+/// Reads the placeholder definitions of a template at `git_ref`, or its
+/// default branch or tag. Git templates are shallow-cloned into a temporary
+/// folder once per ref, or read from the template cache when offline or
+/// when the clone fails.
+pub fn load_placeholders(
+    template: &TemplateEntry,
+    git_ref: Option<&TemplateRef>,
+    offline: bool,
+) -> Result<Vec<Placeholder>, String> {
+    let git_ref = git_ref.map(TemplateRef::name).or(template.git_ref_name());
+    let cached = template_cache::find_cached(template);
+    match &template.source {
+        TemplateSource::Path(path) => read_placeholders(Path::new(path)),
+        TemplateSource::Git(_) if offline => match cached {
+            Some((entry, dir)) => read_cached_placeholders(&entry, &dir, git_ref),
+            None => Err(format!("{} is not cached for offline use", template.name)),
+        },
+        TemplateSource::Git(url) => {
+            let key = (url.clone(), git_ref.map(str::to_string));
+            let loaded = PLACEHOLDERS.get_or_init(Default::default);
+            if let Some(placeholders) = loaded.lock().ok().and_then(|l| l.get(&key).cloned()) {
+                return Ok(placeholders);
+            }
+            let dir = std::env::temp_dir().join(format!(
+                "baker-link-env-template-{}-{}",
+                std::process::id(),
+                chrono::Local::now().timestamp_millis()
+            ));
+            let result = cmd::clone_template(url, git_ref, &dir, &CancellationToken::new())
+                .and_then(|_| read_placeholders(&dir));
+            let _ = std::fs::remove_dir_all(&dir);
+            match (result, cached) {
+                (Ok(placeholders), _) => {
+                    if let Ok(mut loaded) = loaded.lock() {
+                        loaded.insert(key, placeholders.clone());
+                    }
+                    Ok(placeholders)
+                }
+                (Err(_), Some((entry, dir))) => read_cached_placeholders(&entry, &dir, git_ref),
+                (Err(e), None) => Err(e),
+            }
+        }
+    }
+}
+
+fn read_cached_placeholders(
+    entry: &template_cache::CachedTemplate,
+    dir: &Path,
+    git_ref: Option<&str>,
+) -> Result<Vec<Placeholder>, String> {
+    if let Some(warning) = entry.ref_mismatch(git_ref) {
+        crate::log_error(warning);
+    }
+    read_placeholders(dir)
+}
+
+fn read_placeholders(template_dir: &Path) -> Result<Vec<Placeholder>, String> {
+    let path = template_dir.join(CARGO_GENERATE_TOML);
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return Ok(Vec::new());
+    };
+    let config: toml::Value =
+        toml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+    let Some(table) = config.get("placeholders").and_then(toml::Value::as_table) else {
+        return Ok(Vec::new());
+    };
+    Ok(table
+        .iter()
+        .map(|(name, def)| parse_placeholder(name, def))
+        .collect())
+}
+
+fn parse_placeholder(name: &str, def: &toml::Value) -> Placeholder {
+    let choices: Option<Vec<String>> = def.get("choices").and_then(|c| {
+        c.as_array().map(|items| {
+            items
+                .iter()
+                .filter_map(|i| i.as_str().map(str::to_string))
+                .collect()
+        })
+    });
+    let kind = match (choices, def.get("type").and_then(toml::Value::as_str)) {
+        (Some(choices), _) => PlaceholderKind::Choice(choices),
+        (None, Some("bool")) => PlaceholderKind::Bool,
+        _ => PlaceholderKind::Text,
+    };
+    let default = match def.get("default") {
+        Some(toml::Value::String(s)) => s.clone(),
+        Some(toml::Value::Boolean(b)) => b.to_string(),
+        _ => match &kind {
+            PlaceholderKind::Bool => "false".to_string(),
+            PlaceholderKind::Choice(choices) => choices.first().cloned().unwrap_or_default(),
+            PlaceholderKind::Text => String::new(),
+        },
+    };
+    Placeholder {
+        name: name.to_string(),
+        prompt: def
+            .get("prompt")
+            .and_then(toml::Value::as_str)
+            .unwrap_or(name)
+            .to_string(),
+        kind,
+        default,
+        regex: def
+            .get("regex")
+            .and_then(toml::Value::as_str)
+            .map(str::to_string),
+    }
+}
+
+/// Validates every answer and turns them into `GenerateArgs::define` entries.
+pub fn placeholder_defines(
+    placeholders: &[Placeholder],
+    values: &HashMap<String, String>,
+) -> Result<Vec<String>, String> {
+    placeholders
+        .iter()
+        .map(|p| {
+            let value = values.get(&p.name).unwrap_or(&p.default);
+            p.validate(value)?;
+            Ok(format!("{}={}", p.name, value))
+        })
+        .collect()
+}
@@ -0,0 +1,260 @@
+use std::path::{Path, PathBuf};
+
+use crate::{cmd, parameter, settings};
+
+pub const RULES_FILE: &str = "69-baker-link.rules";
+pub const RULES_DIR: &str = "/etc/udev/rules.d";
+
+/// (VID, PID, name) of common debug probes that probe-rs drives. This is a
+/// hand-picked list, not a copy of probe-rs' `69-probe-rs.rules`; `None`
+/// matches every product of the vendor.
+const STANDARD_PROBES: &[(u16, Option<u16>, &str)] = &[
+    (0x0403, Some(0x6010), "FTDI FT2232H"),
+    (0x0403, Some(0x6011), "FTDI FT4232H"),
+    (0x0403, Some(0x6014), "FTDI FT232H"),
+    (0x0483, Some(0x3744), "ST-LINK/V1"),
+    (0x0483, Some(0x3748), "ST-LINK/V2"),
+    (0x0483, Some(0x374b), "ST-LINK/V2-1"),
+    (0x0483, Some(0x374d), "ST-LINK/V3 loader"),
+    (0x0483, Some(0x374e), "ST-LINK/V3"),
+    (0x0483, Some(0x374f), "ST-LINK/V3"),
+    (0x0483, Some(0x3752), "ST-LINK/V2-1"),
+    (0x0483, Some(0x3753), "ST-LINK/V3"),
+    (0x0483, Some(0x3754), "ST-LINK/V3"),
+    (0x0d28, Some(0x0204), "DAPLink"),
+    (0x1366, None, "SEGGER J-Link"),
+    (0x1a86, Some(0x8010), "WCH-Link"),
+    (0x1a86, Some(0x8012), "WCH-Link"),
+    (0x1fc9, Some(0x0090), "NXP LPC-Link2"),
+    (0x1fc9, Some(0x0143), "NXP MCU-Link"),
+    (0x303a, Some(0x1001), "ESP32 USB-JTAG"),
+    (0x303a, Some(0x1002), "ESP32 USB-JTAG"),
+];
+
+/// A USB device node this user cannot open.
+#[derive(Clone, PartialEq)]
+pub struct DeniedDevice {
+    pub name: String,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub node: PathBuf,
+}
+
+impl std::fmt::Display for DeniedDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({:04x}:{:04x}) at {}",
+            self.name,
+            self.vendor_id,
+            self.product_id,
+            self.node.display()
+        )
+    }
+}
+
+fn probe_name(vendor_id: u16, product_id: u16) -> Option<&'static str> {
+    if vendor_id == parameter::BAKER_LINK_VID && product_id == parameter::BAKER_LINK_PID {
+        return Some("Baker link probe");
+    }
+    STANDARD_PROBES
+        .iter()
+        .find(|(vid, pid, _)| *vid == vendor_id && pid.is_none_or(|pid| pid == product_id))
+        .map(|(_, _, name)| *name)
+}
+
+fn rule_line(vendor_id: u16, product_id: Option<u16>) -> String {
+    let product = product_id
+        .map(|pid| format!(", ATTRS{{idProduct}}==\"{:04x}\"", pid))
+        .unwrap_or_default();
+    format!(
+        "ATTRS{{idVendor}}==\"{:04x}\"{}, MODE=\"660\", GROUP=\"plugdev\", TAG+=\"uaccess\"\n",
+        vendor_id, product
+    )
+}
+
+/// Contents of [`RULES_FILE`]: the Baker link probe, [`STANDARD_PROBES`]
+/// and any other CMSIS-DAP probe.
+pub fn rules() -> String {
+    let mut rules = format!(
+        "# udev rules for {} debug probes. Install to {}/{}.\n\
+         ACTION!=\"add|change\", GOTO=\"baker_link_rules_end\"\n\
+         SUBSYSTEM!=\"usb|tty|hidraw\", GOTO=\"baker_link_rules_end\"\n\n\
+         # Baker link on-board probe\n",
+        parameter::APP_NAME,
+        RULES_DIR,
+        RULES_FILE
+    );
+    rules.push_str(&rule_line(
+        parameter::BAKER_LINK_VID,
+        Some(parameter::BAKER_LINK_PID),
+    ));
+    for (vid, pid, name) in STANDARD_PROBES {
+        rules.push_str(&format!("\n# {}\n", name));
+        rules.push_str(&rule_line(*vid, *pid));
+    }
+    rules.push_str(
+        "\n# Other CMSIS-DAP probes\n\
+         ATTRS{product}==\"*CMSIS-DAP*\", MODE=\"660\", GROUP=\"plugdev\", TAG+=\"uaccess\"\n\n\
+         LABEL=\"baker_link_rules_end\"\n",
+    );
+    rules
+}
+
+/// Where [`write_rules`] puts the generated file before it is installed.
+pub fn generated_rules_path() -> PathBuf {
+    settings::config_dir().join(RULES_FILE)
+}
+
+pub fn write_rules() -> Result<PathBuf, String> {
+    let path = generated_rules_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    std::fs::write(&path, rules()).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Shell script that installs the rules file given as `$1` and reloads
+/// udev. Run it as root with `sh -c <script> sh <file>`, so the path is
+/// never parsed by the shell.
+pub fn install_script() -> String {
+    format!(
+        "install -m 644 \"$1\" {}/{} && udevadm control --reload-rules && udevadm trigger",
+        RULES_DIR, RULES_FILE
+    )
+}
+
+/// The command line to show the user, with `elevate` being `pkexec` or `sudo`.
+pub fn install_command(elevate: &str, source: &Path) -> String {
+    format!(
+        "{} sh -c {} sh {}",
+        elevate,
+        cmd::shell_quote(&install_script()),
+        cmd::shell_quote(&source.to_string_lossy())
+    )
+}
+
+/// First rules file that mentions the Baker link probe.
+pub fn installed_rule() -> Option<PathBuf> {
+    let vid = format!("\"{:04x}\"", parameter::BAKER_LINK_VID);
+    let pid = format!("\"{:04x}\"", parameter::BAKER_LINK_PID);
+    [RULES_DIR, "/usr/lib/udev/rules.d", "/lib/udev/rules.d"]
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| {
+            std::fs::read_to_string(path).is_ok_and(|rules| {
+                let rules = rules.to_lowercase();
+                rules.contains(&vid) && rules.contains(&pid)
+            })
+        })
+}
+
+fn read_sysfs(dir: &Path, name: &str) -> Option<String> {
+    std::fs::read_to_string(dir.join(name))
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+/// Known probes whose `/dev/bus/usb` node exists but cannot be opened for
+/// writing. probe-rs skips those silently, so they look like no probe at all.
+/// Always empty outside Linux.
+pub fn denied_devices() -> Vec<DeniedDevice> {
+    if !cfg!(target_os = "linux") {
+        return Vec::new();
+    }
+    let Ok(entries) = std::fs::read_dir("/sys/bus/usb/devices") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let dir = entry.path();
+            let vendor_id = u16::from_str_radix(&read_sysfs(&dir, "idVendor")?, 16).ok()?;
+            let product_id = u16::from_str_radix(&read_sysfs(&dir, "idProduct")?, 16).ok()?;
+            let name = probe_name(vendor_id, product_id)
+                .map(str::to_string)
+                .or_else(|| {
+                    read_sysfs(&dir, "product").filter(|product| product.contains("CMSIS-DAP"))
+                })?;
+            let bus: u32 = read_sysfs(&dir, "busnum")?.parse().ok()?;
+            let device: u32 = read_sysfs(&dir, "devnum")?.parse().ok()?;
+            let node = PathBuf::from(format!("/dev/bus/usb/{:03}/{:03}", bus, device));
+            let denied = std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(&node)
+                .is_err_and(|e| e.kind() == std::io::ErrorKind::PermissionDenied);
+            denied.then_some(DeniedDevice {
+                name,
+                vendor_id,
+                product_id,
+                node,
+            })
+        })
+        .collect()
+}
+
+/// Whether a probe-rs open error is the USB permission problem.
+pub fn is_permission_error(error: &str) -> bool {
+    let error = error.to_lowercase();
+    error.contains("permission denied") || error.contains("access denied")
+}
+
+/// Explanation for probes that are plugged in but not accessible.
+pub fn explain(devices: &[DeniedDevice]) -> String {
+    let list = devices
+        .iter()
+        .map(DeniedDevice::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "Found {}, but this user has no permission to open it. Install the udev rules from Doctor \u{2192} USB permissions and replug the board.",
+        list
+    )
+}
+
+/// The error for an empty probe list: the permission problem when a known
+/// probe is plugged in, "No debug probe found" otherwise.
+pub fn no_probe_error() -> String {
+    let denied = denied_devices();
+    if denied.is_empty() {
+        "No debug probe found".to_string()
+    } else {
+        explain(&denied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_cover_baker_link_and_standard_probes() {
+        let rules = rules();
+        assert!(
+            rules.contains("ATTRS{idVendor}==\"2e8a\", ATTRS{idProduct}==\"000c\", MODE=\"660\"")
+        );
+        assert!(rules.contains("ATTRS{idVendor}==\"1366\", MODE=\"660\""));
+        assert!(rules.contains("ATTRS{product}==\"*CMSIS-DAP*\""));
+        assert!(!rules.contains("\"0003\""));
+        assert!(rules.trim_end().ends_with("LABEL=\"baker_link_rules_end\""));
+    }
+
+    #[test]
+    fn probe_names() {
+        assert_eq!(probe_name(0x2e8a, 0x000c), Some("Baker link probe"));
+        assert_eq!(probe_name(0x1366, 0x0105), Some("SEGGER J-Link"));
+        assert_eq!(probe_name(0x2e8a, 0x0003), None);
+    }
+
+    #[test]
+    fn install_command_quotes_the_path() {
+        let command = install_command("sudo", Path::new("/tmp/it's $(x)/r.rules"));
+        assert!(command.starts_with("sudo sh -c 'install -m 644 \"$1\" "));
+        assert!(command.ends_with(" sh '/tmp/it'\\''s $(x)/r.rules'"));
+    }
+}